@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::future::{Future, poll_fn};
+use std::pin::{Pin, pin};
+use std::task::Poll;
+use std::time::Duration;
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_sdk_dynamodb::config::{AsyncSleep, SharedAsyncSleep};
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::operation::put_item::{PutItemError, PutItemOutput};
+use aws_sdk_dynamodb::operation::query::QueryError;
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_smithy_async::rt::sleep::default_async_sleep;
+
+use crate::request::UpdateRequestSettings;
+use crate::{
+    AcceleratorConfig, AcceleratorRequestExt, AcceleratorResponse, CacheStatus, ItemKey,
+    LOG_TARGET, MomentoAccelerator, Operation,
+};
+
+/// A DynamoDB client that reads through the Momento accelerator and reports whether each read
+/// was served from the cache, as a [Cached] value.
+///
+/// This is for the common read paths, without needing to attach an [AcceleratorResponse] to
+/// each operation. Everything else is available on the wrapped [client](Self::client), which is
+/// accelerated the same way. These reads are also the ones that can be
+/// [hedged](AcceleratorConfig::hedge_after).
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{
+///     AcceleratedDynamoClient, Cached, ItemKey, accelerator_config,
+/// };
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # // The first read misses and fills the cache, the next one hits
+/// # let cached = AtomicBool::new(false);
+/// # let http_client = infallible_client_fn(move |_| {
+/// #     let status = if cached.swap(true, Ordering::Relaxed) { "hit" } else { "miss" };
+/// #     http::Response::builder()
+/// #         .header("x-cache-status", status)
+/// #         .header("x-cache-age-millis", "1500")
+/// #         .body(r#"{"Item": {"id": {"S": "u-1"}, "name": {"S": "Ada"}}}"#)
+/// #         .unwrap()
+/// # });
+/// let client = aws_sdk_dynamodb::Client::from_conf(
+///     aws_sdk_dynamodb::Config::builder()
+/// #       .test_defaults()
+/// #       .http_client(http_client)
+///         .build(),
+/// );
+/// let client = AcceleratedDynamoClient::new(
+///     &client,
+///     accelerator_config()
+///         .cache_name("my-dynamo-cache")
+///         .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///         .auth_token("my-momento-auth-token")
+///         .ttl(Duration::from_secs(60)),
+/// );
+///
+/// let key: ItemKey = [("id".to_string(), AttributeValue::S("u-1".into()))].into();
+/// let read = client.get_item_cached("users", key.clone()).await.expect("get item");
+/// let Cached::FromOrigin(item) = read else {
+///     panic!("the first read can't be cached: {read:?}");
+/// };
+/// assert_eq!(item.unwrap()["name"], AttributeValue::S("Ada".into()));
+///
+/// match client.get_item_cached("users", key).await.expect("get item") {
+///     Cached::FromCache(item, age) => {
+///         assert_eq!(item.unwrap()["name"], AttributeValue::S("Ada".into()));
+///         assert_eq!(age, Some(Duration::from_millis(1500)));
+///     }
+///     read => panic!("the second read should be cached: {read:?}"),
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AcceleratedDynamoClient {
+    client: aws_sdk_dynamodb::Client,
+    hedge: Option<Hedge>,
+    /// The partition and sort key of each table, when writes
+    /// [notify](AcceleratorConfig::cdc_notify) the proxy
+    cdc_key_schemas: Option<HashMap<String, (String, Option<String>)>>,
+}
+
+/// Reads directly from DynamoDB when the proxy is slow to answer, see
+/// [hedge_after](AcceleratorConfig::hedge_after).
+#[derive(Debug, Clone)]
+struct Hedge {
+    /// The client without the accelerator
+    direct: aws_sdk_dynamodb::Client,
+    delay: Duration,
+    sleep: SharedAsyncSleep,
+}
+
+impl AcceleratedDynamoClient {
+    /// Wrap a copy of `client` that routes requests through the accelerator `config` describes.
+    pub fn new(client: &aws_sdk_dynamodb::Client, config: AcceleratorConfig) -> Self {
+        let hedge = config.hedge_delay().and_then(|delay| {
+            Some(Hedge {
+                direct: client.clone(),
+                delay,
+                sleep: client.config().sleep_impl().or_else(default_async_sleep)?,
+            })
+        });
+        let cdc_key_schemas = config.cdc_key_schemas();
+        Self {
+            client: client.clone().with_momento_accelerator(config),
+            hedge,
+            cdc_key_schemas,
+        }
+    }
+
+    /// The accelerated client, for every operation this type has no method for.
+    pub fn client(&self) -> &aws_sdk_dynamodb::Client {
+        &self.client
+    }
+
+    /// Read the item of `table_name` with `key`, and whether it came from the cache.
+    pub async fn get_item_cached(
+        &self,
+        table_name: impl Into<String>,
+        key: ItemKey,
+    ) -> Result<Cached<Option<HashMap<String, AttributeValue>>>, SdkError<GetItemError, HttpResponse>>
+    {
+        let table_name = table_name.into();
+        let response = AcceleratorResponse::new();
+        let proxied = self
+            .client
+            .get_item()
+            .table_name(&table_name)
+            .set_key(Some(key.clone()))
+            .customize()
+            .capture_accelerator_response(&response)
+            .send();
+        let direct = |client: &aws_sdk_dynamodb::Client| {
+            client
+                .get_item()
+                .table_name(table_name)
+                .set_key(Some(key))
+                .send()
+        };
+        let (output, hedged) = self.hedged(proxied, direct).await;
+        Ok(Cached::new(output?.item, &response, hedged))
+    }
+
+    /// Read the first page of items of `table_name` in the partition where `partition_key` is
+    /// `value`, and whether it came from the cache.
+    ///
+    /// Use [client](Self::client) to read further pages or to filter the partition.
+    pub async fn query_cached(
+        &self,
+        table_name: impl Into<String>,
+        partition_key: impl Into<String>,
+        value: AttributeValue,
+    ) -> Result<Cached<Vec<HashMap<String, AttributeValue>>>, SdkError<QueryError, HttpResponse>>
+    {
+        let (table_name, partition_key) = (table_name.into(), partition_key.into());
+        let query = |client: &aws_sdk_dynamodb::Client| {
+            client
+                .query()
+                .table_name(&table_name)
+                .key_condition_expression("#pk = :pk")
+                .expression_attribute_names("#pk", &partition_key)
+                .expression_attribute_values(":pk", value.clone())
+        };
+        let response = AcceleratorResponse::new();
+        let proxied = query(&self.client)
+            .customize()
+            .capture_accelerator_response(&response)
+            .send();
+        let direct = |client: &aws_sdk_dynamodb::Client| query(client).send();
+        let (output, hedged) = self.hedged(proxied, direct).await;
+        Ok(Cached::new(
+            output?.items.unwrap_or_default(),
+            &response,
+            hedged,
+        ))
+    }
+
+    /// Write `item` to `table_name`, and then notify the proxy of it if
+    /// [cdc_notify](AcceleratorConfig::cdc_notify) is enabled.
+    pub async fn put_item(
+        &self,
+        table_name: impl Into<String>,
+        item: HashMap<String, AttributeValue>,
+    ) -> Result<PutItemOutput, SdkError<PutItemError, HttpResponse>> {
+        let table_name = table_name.into();
+        let key = self.cdc_key_schemas.as_ref().and_then(|key_schemas| {
+            let (partition_key, sort_key) = key_schemas.get(&table_name)?;
+            let mut key = ItemKey::new();
+            for attribute in std::iter::once(partition_key).chain(sort_key) {
+                key.insert(attribute.clone(), item.get(attribute)?.clone());
+            }
+            Some(key)
+        });
+        let output = self
+            .client
+            .put_item()
+            .table_name(&table_name)
+            .set_item(Some(item))
+            .send()
+            .await?;
+        if let Some(key) = key {
+            self.notify(&table_name, key, Operation::PutItem).await;
+        }
+        Ok(output)
+    }
+
+    /// Tell the proxy that `operation` changed the item of `table_name` with `key`.
+    async fn notify(&self, table_name: &str, key: ItemKey, operation: Operation) {
+        let result = self
+            .client
+            .get_item()
+            .table_name(table_name)
+            .set_key(Some(key))
+            .customize()
+            .interceptor(UpdateRequestSettings::new(move |settings| {
+                settings.evict = true;
+                settings.cdc_notify = Some(operation);
+            }))
+            .send()
+            .await;
+        if let Err(e) = result {
+            log::warn!(target: LOG_TARGET, "failed to notify the proxy of a write to {table_name}: {e}");
+        }
+    }
+
+    /// Wait for the `proxied` read, and if it takes longer than the hedge delay, also for the
+    /// `direct` one, returning whichever finishes first and whether that was the direct read.
+    async fn hedged<T, F>(
+        &self,
+        proxied: impl Future<Output = T>,
+        direct: impl FnOnce(&aws_sdk_dynamodb::Client) -> F,
+    ) -> (T, bool)
+    where
+        F: Future<Output = T>,
+    {
+        let Some(hedge) = &self.hedge else {
+            return (proxied.await, false);
+        };
+        let mut proxied = pin!(proxied);
+        let mut delay = pin!(hedge.sleep.sleep(hedge.delay));
+        let mut direct = Some(direct);
+        let mut direct_read: Option<Pin<Box<F>>> = None;
+        // The read that loses is dropped with this future, which cancels it
+        poll_fn(|cx| {
+            if let Poll::Ready(output) = proxied.as_mut().poll(cx) {
+                return Poll::Ready((output, false));
+            }
+            if let Some(direct) = direct.take_if(|_| delay.as_mut().poll(cx).is_ready()) {
+                direct_read = Some(Box::pin(direct(&hedge.direct)));
+            }
+            match &mut direct_read {
+                Some(read) => read.as_mut().poll(cx).map(|output| (output, true)),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
+
+/// The result of a read by [AcceleratedDynamoClient], and whether it was served from the cache.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{AcceleratedDynamoClient, Cached, accelerator_config};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # // The first query misses and fills the cache, the next one hits
+/// # let cached = AtomicBool::new(false);
+/// # let http_client = infallible_client_fn(move |_| {
+/// #     let status = if cached.swap(true, Ordering::Relaxed) { "hit" } else { "miss" };
+/// #     http::Response::builder()
+/// #         .header("x-cache-status", status)
+/// #         .body(r#"{"Items": [{"id": {"S": "u-1"}}], "Count": 1}"#)
+/// #         .unwrap()
+/// # });
+/// # let client = aws_sdk_dynamodb::Client::from_conf(
+/// #     aws_sdk_dynamodb::Config::builder()
+/// #         .test_defaults()
+/// #         .http_client(http_client)
+/// #         .build(),
+/// # );
+/// let client = AcceleratedDynamoClient::new(
+///     &client,
+///     accelerator_config()
+///         .cache_name("my-dynamo-cache")
+///         .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///         .auth_token("my-momento-auth-token")
+///         .ttl(Duration::from_secs(60)),
+/// );
+/// let query = async || {
+///     client
+///         .query_cached("users", "id", AttributeValue::S("u-1".into()))
+///         .await
+///         .expect("query")
+/// };
+///
+/// let miss = query().await;
+/// assert!(!miss.is_from_cache());
+/// assert!(matches!(miss, Cached::FromOrigin(_)));
+/// assert_eq!(miss.get().len(), 1);
+///
+/// let hit = query().await;
+/// assert!(hit.is_from_cache());
+/// // No age was reported
+/// assert!(matches!(hit, Cached::FromCache(_, None)));
+/// assert_eq!(hit.into_inner(), miss.into_inner());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cached<T> {
+    /// Served from the proxy's cache or the [local cache](AcceleratorConfig::local_cache), with
+    /// its [age](AcceleratorResponse::cache_age) when the proxy reported it.
+    FromCache(T, Option<Duration>),
+    /// Read from DynamoDB, by the proxy, directly, or by a [hedged](AcceleratorConfig::hedge_after)
+    /// read. This includes responses the proxy didn't say were cached.
+    FromOrigin(T),
+}
+
+impl<T> Cached<T> {
+    /// The value of a read that `response` describes, which DynamoDB answered if the `hedged`
+    /// read won.
+    fn new(value: T, response: &AcceleratorResponse, hedged: bool) -> Self {
+        match response.cache_status() {
+            CacheStatus::Hit if !hedged => Self::FromCache(value, response.cache_age()),
+            _ => Self::FromOrigin(value),
+        }
+    }
+
+    /// Whether the value was served from the cache.
+    pub fn is_from_cache(&self) -> bool {
+        matches!(self, Self::FromCache(..))
+    }
+
+    /// The value, wherever it came from.
+    pub fn get(&self) -> &T {
+        match self {
+            Self::FromCache(value, _) | Self::FromOrigin(value) => value,
+        }
+    }
+
+    /// The value, wherever it came from.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::FromCache(value, _) | Self::FromOrigin(value) => value,
+        }
+    }
+}
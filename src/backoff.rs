@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use aws_smithy_runtime_api::client::interceptors::context::InterceptorContext;
+use aws_smithy_runtime_api::client::retries::classifiers::{
+    ClassifyRetry, RetryAction, RetryClassifierPriority,
+};
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use aws_smithy_types::retry::ErrorKind;
+
+use crate::{AcceleratorConfig, Rng};
+
+/// How much of each proxy retry backoff is randomized, so that many clients retrying against a
+/// recovering proxy spread out instead of retrying in lockstep. See
+/// [AcceleratorConfig::proxy_retry_backoff](crate::AcceleratorConfig::proxy_retry_backoff).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum Jitter {
+    /// Wait exactly the exponential backoff.
+    None,
+    /// Wait anywhere from zero up to the exponential backoff.
+    #[default]
+    Full,
+    /// Wait at least half of the exponential backoff, and at most all of it.
+    Equal,
+}
+
+/// How many failed proxied attempts the operation has retried, for
+/// [max_proxy_retries](AcceleratorConfig::max_proxy_retries)
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ProxyRetries(pub(crate) u32);
+
+impl Storable for ProxyRetries {
+    type Storer = StoreReplace<Self>;
+}
+
+impl AcceleratorConfig {
+    /// Back off from `base` up to `max` between a failed proxied attempt and its retry,
+    /// randomized by `jitter`, instead of with the client's retry backoff.
+    ///
+    /// This applies to the proxy's transient failures: a 500, 502, 503 or 504, or no response
+    /// at all. The backoff doubles after each attempt, starting from `base`, and is capped at
+    /// `max` before the jitter is applied. How many attempts are made is still up to the
+    /// client's retry policy and [read_retries](Self::read_retries), and the client's
+    /// `max_backoff` still caps the wait. Other errors, and attempts sent directly to DynamoDB,
+    /// keep the client's backoff. The jitter is drawn from the [rng](Self::rng).
+    ///
+    /// The backoff is installed by [with_momento_accelerator](crate::MomentoAccelerator) and
+    /// [with_proxy_interceptor](crate::MomentoAccelerator::with_proxy_interceptor), so a
+    /// [ProxyInterceptor](crate::ProxyInterceptor) added to a client with `interceptor()` keeps
+    /// the client's backoff. `base` must be at most `max`, or [build](Self::build) reports a
+    /// [ConfigError::OutOfRange](crate::ConfigError::OutOfRange).
+    ///
+    /// # Example
+    /// ```
+    /// # use aws_sdk_dynamodb::config::{AsyncSleep, Sleep};
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_sdk_dynamodb::config::StalledStreamProtectionConfig;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{Jitter, MomentoAccelerator, Rng, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// /// Random numbers from a fixed sequence
+    /// #[derive(Debug)]
+    /// struct FixedRng(Mutex<std::iter::Cycle<std::array::IntoIter<f64, 3>>>);
+    ///
+    /// impl Rng for FixedRng {
+    ///     fn next_f64(&self) -> f64 {
+    ///         self.0.lock().unwrap().next().unwrap()
+    ///     }
+    /// }
+    ///
+    /// # /// Records how long the client backs off, without waiting
+    /// # #[derive(Debug, Clone, Default)]
+    /// # struct RecordingSleep(Arc<Mutex<Vec<Duration>>>);
+    /// # impl AsyncSleep for RecordingSleep {
+    /// #     fn sleep(&self, duration: Duration) -> Sleep {
+    /// #         self.0.lock().unwrap().push(duration);
+    /// #         Sleep::new(async {})
+    /// #     }
+    /// # }
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let sleep = RecordingSleep::default();
+    /// # // The proxy is down
+    /// # let http_client =
+    /// #     infallible_client_fn(|_| http::Response::builder().status(503).body("{}").unwrap());
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    /// #   .sleep_impl(sleep.clone())
+    /// #   .stalled_stream_protection(StalledStreamProtectionConfig::disabled())
+    ///     .retry_config(RetryConfig::standard().with_max_attempts(5))
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .proxy_retry_backoff(
+    ///                 Duration::from_millis(100),
+    ///                 Duration::from_millis(300),
+    ///                 Jitter::Equal,
+    ///             )
+    ///             .rng(FixedRng(Mutex::new([0.0, 0.5, 0.999].into_iter().cycle()))),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let read = client.get_item().table_name("my-table").send().await;
+    /// assert!(read.is_err());
+    ///
+    /// // Each backoff is between half and all of 100ms, 200ms, 300ms (capped), and 300ms
+    /// # // The client also sleeps for a 5s timeout of its own during each attempt
+    /// # let mut backoffs = sleep.0.lock().unwrap().clone();
+    /// # backoffs.retain(|sleep| *sleep < Duration::from_secs(5));
+    /// let nominal = [100, 200, 300, 300].map(Duration::from_millis);
+    /// assert_eq!(backoffs.len(), nominal.len());
+    /// for (backoff, nominal) in backoffs.iter().zip(nominal) {
+    ///     assert!(nominal / 2 <= *backoff && *backoff <= nominal);
+    /// }
+    /// assert_eq!(backoffs[0], Duration::from_millis(50));
+    /// assert_eq!(backoffs[1], Duration::from_millis(150));
+    /// # }
+    /// ```
+    pub fn proxy_retry_backoff(mut self, base: Duration, max: Duration, jitter: Jitter) -> Self {
+        self.proxy_retry_backoff = Some(ProxyRetryBackoff { base, max, jitter });
+        self
+    }
+
+    /// Retry a failed proxied attempt at most `retries` times per operation, across every
+    /// proxy-specific retry.
+    ///
+    /// A proxy that keeps failing is retried by several behaviors at once: the client's transient
+    /// error retries, [proxy_retry_backoff](Self::proxy_retry_backoff),
+    /// [fallback_cache](Self::fallback_cache),
+    /// [follow_proxy_redirects](Self::follow_proxy_redirects) and [hit_timeout](Self::hit_timeout).
+    /// This caps them together, by refusing to retry once an operation has retried `retries` failed
+    /// proxied attempts. A proxied attempt fails when the proxy doesn't respond, responds with a
+    /// server error or a redirect, or has no cache for the request.
+    ///
+    /// Proxy retries are attempts of the same operation, so the client's `max_attempts`, plus any
+    /// [read_retries](Self::read_retries), still caps the attempts in total and the two limits
+    /// never multiply: an operation makes at most `max_attempts + read_retries` attempts, and at
+    /// most `retries` of them follow a failed proxied attempt. Attempts that went directly to
+    /// DynamoDB, like after the [circuit breaker](Self::circuit_breaker) opens, are retried as the
+    /// client's policy says.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::atomic::{AtomicU32, Ordering};
+    /// # use std::sync::Arc;
+    /// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let attempts = Arc::new(AtomicU32::new(0));
+    /// # // The proxy is down
+    /// # let http_client = infallible_client_fn({
+    /// #     let attempts = attempts.clone();
+    /// #     move |_| {
+    /// #         attempts.fetch_add(1, Ordering::Relaxed);
+    /// #         http::Response::builder().status(503).body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .retry_config(
+    ///         RetryConfig::standard()
+    ///             .with_max_attempts(5)
+    ///             .with_initial_backoff(Duration::from_millis(1)),
+    ///     )
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .max_proxy_retries(1),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let read = client.get_item().table_name("my-table").send().await;
+    /// assert!(read.is_err());
+    /// // The first attempt and one retry, rather than the client's 5 attempts
+    /// assert_eq!(attempts.load(Ordering::Relaxed), 2);
+    /// # }
+    /// ```
+    pub fn max_proxy_retries(mut self, retries: u32) -> Self {
+        self.max_proxy_retries = Some(retries);
+        self
+    }
+
+    /// Whether an option that decides retries with [ProxyRetryClassifier] is set.
+    pub(crate) fn explicit_retries(&self) -> bool {
+        self.proxy_retry_backoff.is_some()
+            || self.fallback_cache.is_some()
+            || self.follow_proxy_redirects
+            || self.max_proxy_retries.is_some()
+    }
+}
+
+/// The backoff between a failed proxied attempt and its retry.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ProxyRetryBackoff {
+    pub(crate) base: Duration,
+    pub(crate) max: Duration,
+    pub(crate) jitter: Jitter,
+}
+
+impl ProxyRetryBackoff {
+    /// How long to wait before retrying after `attempts` attempts: `base` doubled for each
+    /// attempt after the first, up to `max`, then jittered.
+    pub(crate) fn delay(&self, attempts: u32, rng: &dyn Rng) -> Duration {
+        let exponential = 2_u32
+            .checked_pow(attempts.saturating_sub(1))
+            .and_then(|factor| self.base.checked_mul(factor))
+            .map_or(self.max, |backoff| backoff.min(self.max));
+        // Keep a misbehaving Rng from stretching the backoff past the exponential one
+        let random = rng.next_f64().clamp(0.0, 1.0);
+        match self.jitter {
+            Jitter::None => exponential,
+            Jitter::Full => exponential.mul_f64(random),
+            Jitter::Equal => exponential / 2 + (exponential / 2).mul_f64(random),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PendingRetry {
+    After(Duration),
+    Forbidden,
+}
+
+/// What the interceptor chose for each operation's attempt that just finished, until its
+/// [ProxyRetryClassifier] classifies it.
+///
+/// Retry classifiers only see the attempt's context, not its config bag, so the interceptor's
+/// `read_after_attempt` hands the choice over here, keyed by the address of that context. The
+/// context stays in place for the whole operation, and the interceptor clears its entry when
+/// each attempt finishes and once the operation completes, so an entry never outlives it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PendingRetries(Arc<Mutex<HashMap<usize, PendingRetry>>>);
+
+impl PendingRetries {
+    fn key(context: &InterceptorContext) -> usize {
+        std::ptr::from_ref(context) as usize
+    }
+
+    fn set(&self, context: &InterceptorContext, pending: Option<PendingRetry>) {
+        let mut retries = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        match pending {
+            Some(pending) => retries.insert(Self::key(context), pending),
+            None => retries.remove(&Self::key(context)),
+        };
+    }
+
+    /// Have [ProxyRetryClassifier] retry the attempt of `context` that just finished after
+    /// `delay`, or leave it to the other classifiers.
+    pub(crate) fn set_delay(&self, context: &InterceptorContext, delay: Option<Duration>) {
+        self.set(context, delay.map(PendingRetry::After));
+    }
+
+    /// Have [ProxyRetryClassifier] refuse to retry the attempt of `context` that just finished,
+    /// see [max_proxy_retries](crate::AcceleratorConfig::max_proxy_retries).
+    pub(crate) fn forbid(&self, context: &InterceptorContext) {
+        self.set(context, Some(PendingRetry::Forbidden));
+    }
+
+    /// Forget any choice for `context`, leaving its attempts to the other classifiers.
+    pub(crate) fn clear(&self, context: &InterceptorContext) {
+        self.set(context, None);
+    }
+
+    fn take(&self, context: &InterceptorContext) -> Option<PendingRetry> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&Self::key(context))
+    }
+}
+
+/// Retries a failed proxied attempt after the backoff the interceptor chose for it, or stops
+/// retrying it.
+///
+/// It runs after the SDK's classifiers, so it overrides them. It only retries attempts they
+/// would also retry as transient, server errors and connection failures, and only forbids
+/// retrying proxied attempts past [max_proxy_retries](crate::AcceleratorConfig::max_proxy_retries).
+#[derive(Debug)]
+pub(crate) struct ProxyRetryClassifier(pub(crate) PendingRetries);
+
+impl ProxyRetryClassifier {
+    pub(crate) const NAME: &'static str = "MomentoProxyRetryBackoff";
+}
+
+impl ClassifyRetry for ProxyRetryClassifier {
+    fn classify_retry(&self, ctx: &InterceptorContext) -> RetryAction {
+        match self.0.take(ctx) {
+            Some(PendingRetry::After(delay)) => {
+                RetryAction::retryable_error_with_explicit_delay(ErrorKind::TransientError, delay)
+            }
+            Some(PendingRetry::Forbidden) => RetryAction::RetryForbidden,
+            None => RetryAction::NoActionIndicated,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn priority(&self) -> RetryClassifierPriority {
+        RetryClassifierPriority::run_after(RetryClassifierPriority::transient_error_classifier())
+    }
+}
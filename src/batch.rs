@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::batch_get_item::{BatchGetItemError, BatchGetItemOutput};
+use aws_sdk_dynamodb::types::KeysAndAttributes;
+
+/// The most keys DynamoDB accepts in one BatchGetItem
+const DYNAMODB_MAX_BATCH_KEYS: usize = 100;
+
+/// Send a BatchGetItem as several smaller ones of at most `max_batch_keys` keys, and merge them.
+///
+/// A proxy may cache small batches more effectively than DynamoDB's limit of 100 keys. The
+/// interceptor can only change a request, not send more of them, so this splits the batch on the
+/// client instead. `max_batch_keys` is clamped to between 1 and 100. Each table's options, like
+/// `ProjectionExpression`, are kept for every chunk with its keys.
+///
+/// The chunks are sent one after another. Their `Responses`, `UnprocessedKeys` and
+/// `ConsumedCapacity` are merged into one output, so it can be handled like the output of a
+/// single BatchGetItem. If any chunk fails, that error is returned.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use aws_sdk_dynamodb::types::{AttributeValue, KeysAndAttributes};
+/// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config, chunked_batch_get_item};
+/// use std::collections::HashMap;
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let chunk_sizes = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let chunk_sizes = chunk_sizes.clone();
+/// #     move |request| {
+/// #         // Return one item for each key requested
+/// #         let body = std::str::from_utf8(request.body().bytes().unwrap()).unwrap();
+/// #         let keys = body.matches(r#""pk":"#).count();
+/// #         chunk_sizes.lock().unwrap().push(keys);
+/// #         let items = vec![r#"{"pk": {"S": "item"}}"#; keys].join(",");
+/// #         http::Response::builder()
+/// #             .body(format!(r#"{{"Responses": {{"my-table": [{items}]}}}}"#))
+/// #             .unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let request_items = |keys: usize| {
+///     let keys = (0..keys)
+///         .map(|i| [("pk".to_string(), AttributeValue::S(i.to_string()))].into())
+///         .collect();
+///     let keys = KeysAndAttributes::builder().set_keys(Some(keys)).build().unwrap();
+///     HashMap::from([("my-table".to_string(), keys)])
+/// };
+///
+/// let output = chunked_batch_get_item(&client, request_items(5), 2).await.expect("batch get");
+/// assert_eq!(output.responses().unwrap()["my-table"].len(), 5);
+/// # assert_eq!(*chunk_sizes.lock().unwrap(), [2, 2, 1]);
+///
+/// // A batch right at the limit is sent as it is
+/// # chunk_sizes.lock().unwrap().clear();
+/// let output = chunked_batch_get_item(&client, request_items(2), 2).await.expect("batch get");
+/// assert_eq!(output.responses().unwrap()["my-table"].len(), 2);
+/// # assert_eq!(*chunk_sizes.lock().unwrap(), [2]);
+/// # }
+/// ```
+pub async fn chunked_batch_get_item(
+    client: &aws_sdk_dynamodb::Client,
+    request_items: HashMap<String, KeysAndAttributes>,
+    max_batch_keys: usize,
+) -> Result<BatchGetItemOutput, SdkError<BatchGetItemError, HttpResponse>> {
+    let max_batch_keys = max_batch_keys.clamp(1, DYNAMODB_MAX_BATCH_KEYS);
+    let mut merged = BatchGetItemOutput::builder().build();
+    for chunk in chunks(request_items, max_batch_keys) {
+        let output = client
+            .batch_get_item()
+            .set_request_items(Some(chunk))
+            .send()
+            .await?;
+        merge(&mut merged, output);
+    }
+    Ok(merged)
+}
+
+/// Split `request_items` into batches of at most `max_batch_keys` keys.
+fn chunks(
+    request_items: HashMap<String, KeysAndAttributes>,
+    max_batch_keys: usize,
+) -> Vec<HashMap<String, KeysAndAttributes>> {
+    let mut chunks = Vec::new();
+    let mut chunk = HashMap::new();
+    let mut chunk_keys = 0;
+    for (table, mut keys_and_attributes) in request_items {
+        let mut keys = std::mem::take(&mut keys_and_attributes.keys);
+        while !keys.is_empty() {
+            let take = keys.len().min(max_batch_keys - chunk_keys);
+            let mut table_chunk = keys_and_attributes.clone();
+            table_chunk.keys = keys.drain(..take).collect();
+            chunk.insert(table.clone(), table_chunk);
+            chunk_keys += take;
+            if chunk_keys == max_batch_keys {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_keys = 0;
+            }
+        }
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+fn merge(merged: &mut BatchGetItemOutput, output: BatchGetItemOutput) {
+    for (table, items) in output.responses.unwrap_or_default() {
+        merged
+            .responses
+            .get_or_insert_default()
+            .entry(table)
+            .or_default()
+            .extend(items);
+    }
+    for (table, unprocessed) in output.unprocessed_keys.unwrap_or_default() {
+        match merged.unprocessed_keys.get_or_insert_default().entry(table) {
+            Entry::Occupied(mut existing) => existing.get_mut().keys.extend(unprocessed.keys),
+            Entry::Vacant(entry) => {
+                entry.insert(unprocessed);
+            }
+        }
+    }
+    if let Some(consumed_capacity) = output.consumed_capacity {
+        merged
+            .consumed_capacity
+            .get_or_insert_default()
+            .extend(consumed_capacity);
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+/// Routes around the proxy for a while after it fails repeatedly.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<SystemTime>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Default::default(),
+        }
+    }
+
+    /// Whether requests may be sent to the proxy at `now`.
+    pub(crate) fn allows(&self, now: SystemTime) -> bool {
+        match self.state().open_until {
+            Some(open_until) => open_until <= now,
+            None => true,
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        let mut state = self.state();
+        state.consecutive_failures = 0;
+        state.open_until = None;
+    }
+
+    pub(crate) fn record_failure(&self, now: SystemTime, log_target: &str) {
+        let mut state = self.state();
+        state.consecutive_failures += 1;
+        if self.failure_threshold <= state.consecutive_failures {
+            log::warn!(
+                target: log_target,
+                "momento proxy failed {failures} times in a row, bypassing it for {cooldown:?}",
+                failures = state.consecutive_failures,
+                cooldown = self.cooldown
+            );
+            // After the cooldown the proxy gets another full set of attempts
+            state.consecutive_failures = 0;
+            state.open_until = Some(now + self.cooldown);
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::hash_map::RandomState;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+/// A source of the current time for the accelerator's time-based behavior, like the
+/// [circuit breaker](crate::AcceleratorConfig::circuit_breaker).
+///
+/// The default is [SystemClock]. Tests can supply their own clock through
+/// [AcceleratorConfig::clock](crate::AcceleratorConfig::clock) to control time precisely.
+pub trait Clock: Debug + Send + Sync {
+    /// The current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// A [Clock] that reads the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A source of randomness for the accelerator's randomized behavior, like the jitter of
+/// [proxy_retry_backoff](crate::AcceleratorConfig::proxy_retry_backoff).
+///
+/// The default is [SystemRng]. Tests can supply their own through
+/// [AcceleratorConfig::rng](crate::AcceleratorConfig::rng) to make that behavior deterministic.
+pub trait Rng: Debug + Send + Sync {
+    /// A number uniformly distributed in `[0, 1)`.
+    fn next_f64(&self) -> f64;
+}
+
+/// An [Rng] seeded by the standard library's per-process random hash keys.
+///
+/// It is not cryptographically secure, which jitter doesn't need.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRng;
+
+impl Rng for SystemRng {
+    fn next_f64(&self) -> f64 {
+        static CALLS: AtomicU64 = AtomicU64::new(0);
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(CALLS.fetch_add(1, Ordering::Relaxed));
+        // The top 53 bits fill an f64's mantissa exactly
+        (hasher.finish() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
+
+/// 128 random bits as 32 hex digits, seeded like [SystemRng], for identifiers that only need
+/// to be unique, like [client_instance_id](crate::AcceleratorConfig::client_instance_id)'s.
+pub(crate) fn random_id() -> String {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let mut halves = [0, 1].map(|half| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(CALLS.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u8(half);
+        hasher.finish()
+    });
+    halves[1] ^= u64::from(std::process::id());
+    format!("{:016x}{:016x}", halves[0], halves[1])
+}
@@ -0,0 +1,135 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use crate::AcceleratorConfig;
+
+/// How many requests have been proxied in the current window, for
+/// [cost_budget](AcceleratorConfig::cost_budget).
+///
+/// Windows are fixed, counted from the Unix epoch, so the whole budget is available again as
+/// each one starts.
+#[derive(Debug)]
+pub(crate) struct CostBudget {
+    units: u64,
+    window: Duration,
+    /// The number of the current window since the Unix epoch, and the units spent in it
+    spent: Mutex<(u128, u64)>,
+}
+
+impl CostBudget {
+    /// A budget of `units` proxied requests every `window`.
+    pub(crate) fn new(units: u64, window: Duration) -> Self {
+        Self {
+            units,
+            window,
+            spent: Mutex::new((0, 0)),
+        }
+    }
+
+    /// Spend a unit on a request at `now`, returning the window it was spent in, or `None` if
+    /// the window had none left.
+    pub(crate) fn try_spend(&self, now: SystemTime) -> Option<u128> {
+        let since_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let index = since_epoch / self.window.as_nanos().max(1);
+        let mut spent = self.spent.lock().unwrap_or_else(PoisonError::into_inner);
+        if spent.0 != index {
+            *spent = (index, 0);
+        }
+        if spent.1 >= self.units {
+            return None;
+        }
+        spent.1 += 1;
+        Some(index)
+    }
+
+    /// Give back a unit spent in `window` on a request that didn't go to the proxy after all.
+    pub(crate) fn refund(&self, window: u128) {
+        let mut spent = self.spent.lock().unwrap_or_else(PoisonError::into_inner);
+        if spent.0 == window {
+            spent.1 = spent.1.saturating_sub(1);
+        }
+    }
+}
+
+impl AcceleratorConfig {
+    /// Proxy at most `units` requests every `window`, and send the rest directly to DynamoDB with
+    /// [SkipReason::CostBudgetExhausted](crate::SkipReason::CostBudgetExhausted) until the next
+    /// window starts.
+    ///
+    /// This caps what the cache tier is asked to do when it is billed per request, while the
+    /// reads over the budget still succeed against DynamoDB. Each request that would otherwise
+    /// be sent to the proxy costs one unit, including retried attempts; requests that skip the
+    /// proxy for another reason, are turned away by [max_inflight](Self::max_inflight), can't
+    /// be rewritten for the proxy, or are answered by the [local_cache](Self::local_cache), cost
+    /// nothing. Windows are measured with the [clock](Self::clock) and start at fixed times, so
+    /// the whole budget comes back at once. Both `units` and `window` must be non-zero, or
+    /// [build](Self::build) reports a [ConfigError::OutOfRange](crate::ConfigError::OutOfRange).
+    ///
+    /// The budget is shared by every client that uses the same interceptor.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, Clock, MomentoAccelerator, SkipReason,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct FakeClock(Arc<Mutex<SystemTime>>);
+    /// impl Clock for FakeClock {
+    ///     fn now(&self) -> SystemTime {
+    ///         *self.0.lock().unwrap()
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let http_client = infallible_client_fn(|_| http::Response::builder().body("{}").unwrap());
+    /// let clock = FakeClock(Arc::new(Mutex::new(SystemTime::UNIX_EPOCH)));
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .cost_budget(2, Duration::from_secs(60))
+    ///             .clock(clock.clone()),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let skip_reason = async || {
+    ///     let response = AcceleratorResponse::new();
+    ///     let _ = client
+    ///         .get_item()
+    ///         .table_name("my-table")
+    ///         .customize()
+    ///         .capture_accelerator_response(&response)
+    ///         .send()
+    ///         .await;
+    ///     response.skip_reason()
+    /// };
+    ///
+    /// assert_eq!(skip_reason().await, None);
+    /// assert_eq!(skip_reason().await, None);
+    /// // The budget for this minute is spent
+    /// assert_eq!(skip_reason().await, Some(SkipReason::CostBudgetExhausted));
+    /// // And back for the next one
+    /// *clock.0.lock().unwrap() += Duration::from_secs(60);
+    /// assert_eq!(skip_reason().await, None);
+    /// # }
+    /// ```
+    pub fn cost_budget(mut self, units: u64, window: Duration) -> Self {
+        self.cost_budget = Some((units, window));
+        self
+    }
+}
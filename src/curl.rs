@@ -0,0 +1,49 @@
+use aws_sdk_dynamodb::config::http::HttpRequest;
+
+/// What the Momento auth token is replaced with in a cURL command
+const AUTH_TOKEN_PLACEHOLDER: &str = "<momento-auth-token>";
+
+/// A cURL command that sends `request`, for
+/// [curl_command](crate::ProxyInterceptor::curl_command).
+///
+/// The value of the `auth_header`, and `auth_token` wherever it appears in the URI, are replaced
+/// with a placeholder.
+pub(crate) fn command(
+    request: &HttpRequest,
+    auth_header: &str,
+    auth_token: Option<&str>,
+) -> String {
+    let mut uri = request.uri().to_string();
+    if let Some(auth_token) = auth_token {
+        uri = uri.replace(auth_token, AUTH_TOKEN_PLACEHOLDER);
+    }
+    let mut lines = vec![
+        format!(
+            "# Replace {AUTH_TOKEN_PLACEHOLDER} with your Momento auth token. The body is signed, so send it unchanged, within 5 minutes of x-amz-date."
+        ),
+        format!("curl -X {} {}", request.method(), quote(&uri)),
+    ];
+    for (name, value) in request.headers() {
+        let value = if name.eq_ignore_ascii_case(auth_header) {
+            AUTH_TOKEN_PLACEHOLDER
+        } else {
+            value
+        };
+        lines.push(format!("  -H {}", quote(&format!("{name}: {value}"))));
+    }
+    match request.body().bytes().map(std::str::from_utf8) {
+        Some(Ok(body)) if !body.is_empty() => {
+            lines.push(format!("  --data-binary {}", quote(body)));
+        }
+        Some(Ok(_)) | None => {}
+        // cURL can read anything from a file, but this has to stay a single command
+        Some(Err(_)) => lines.push("  # the body is not UTF-8 and is left out".to_string()),
+    }
+    let (comment, command) = lines.split_first().expect("the command is never empty");
+    format!("{comment}\n{}", command.join(" \\\n"))
+}
+
+/// Quote `value` as a single shell word.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
@@ -0,0 +1,32 @@
+use crate::SkipReason;
+
+/// What the accelerator decided to do with one request attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "decision", rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum RewriteDecision {
+    /// The request was rewritten and sent to the proxy.
+    Proxy {
+        /// The proxy URI the request was sent to
+        uri: String,
+    },
+    /// The request was sent directly to DynamoDB.
+    Skip {
+        /// Why the proxy was skipped
+        reason: SkipReason,
+    },
+    /// The response came from the [local cache](crate::AcceleratorConfig::local_cache) and the
+    /// request was not sent at all.
+    LocalCacheHit,
+    /// The [negative bloom filter](crate::AcceleratorConfig::negative_bloom) reported the item
+    /// absent and the request was not sent at all.
+    KnownAbsent,
+    /// The response came from the simulated proxy of
+    /// [ProxyMode::Simulated](crate::ProxyMode::Simulated) and the request was not sent at all.
+    #[cfg(feature = "simulated")]
+    Simulated,
+}
@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::config::http::HttpRequest;
+use aws_sdk_dynamodb::error::BoxError;
+use aws_smithy_types::Document;
+use aws_smithy_types::body::SdkBody;
+
+use crate::{AcceleratorConfig, ItemKey, Operation, ProxyInterceptor, RewriteDecision, json};
+
+/// Where sample requests are addressed, as if from a client in `us-east-1`
+const SAMPLE_ENDPOINT: &str = "https://dynamodb.us-east-1.amazonaws.com/";
+
+/// One request of a sample workload for [simulate]: an operation on a table, and optionally
+/// the key of the item it reads or writes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SampleRequest {
+    operation: Operation,
+    table: String,
+    key: Option<ItemKey>,
+}
+
+impl SampleRequest {
+    /// A request for `operation` on `table`, without a key.
+    pub fn new(operation: Operation, table: impl Into<String>) -> Self {
+        Self {
+            operation,
+            table: table.into(),
+            key: None,
+        }
+    }
+
+    /// The key of the item the request is for.
+    ///
+    /// It is the `Key` of a GetItem, UpdateItem, or DeleteItem, the `Item` of a PutItem, the
+    /// only key of a batch or transaction, and the key condition of a Query.
+    pub fn key(mut self, key: ItemKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// The request as the SDK would send it, before signing.
+    fn to_request(&self) -> Result<HttpRequest, BoxError> {
+        let request = http::Request::builder()
+            .method("POST")
+            .uri(SAMPLE_ENDPOINT)
+            .header("content-type", "application/x-amz-json-1.0")
+            .header(
+                "x-amz-target",
+                format!("DynamoDB_20120810.{}", self.operation.name()),
+            )
+            .body(SdkBody::from(json::serialize(&self.body())))?;
+        Ok(HttpRequest::try_from(request)?)
+    }
+
+    /// The JSON body of the request.
+    fn body(&self) -> Document {
+        let object = |fields: Vec<(&str, Document)>| {
+            Document::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value))
+                    .collect(),
+            )
+        };
+        let table = || ("TableName", Document::String(self.table.clone()));
+        let Some(key) = self.key.as_ref().and_then(json::item_document) else {
+            return object(vec![table()]);
+        };
+        let item = |name| object(vec![table(), (name, key.clone())]);
+        match self.operation {
+            Operation::GetItem | Operation::UpdateItem | Operation::DeleteItem => item("Key"),
+            Operation::PutItem => item("Item"),
+            Operation::Query => {
+                let Document::Object(attributes) = &key else {
+                    return object(vec![table()]);
+                };
+                let mut attributes: Vec<_> = attributes.iter().collect();
+                attributes.sort_unstable_by_key(|(name, _)| name.as_str());
+                let mut names = HashMap::new();
+                let mut values = HashMap::new();
+                let mut conditions = Vec::new();
+                for (i, (name, value)) in attributes.into_iter().enumerate() {
+                    names.insert(format!("#k{i}"), Document::String(name.clone()));
+                    values.insert(format!(":k{i}"), value.clone());
+                    conditions.push(format!("#k{i} = :k{i}"));
+                }
+                object(vec![
+                    table(),
+                    (
+                        "KeyConditionExpression",
+                        Document::String(conditions.join(" AND ")),
+                    ),
+                    ("ExpressionAttributeNames", Document::Object(names)),
+                    ("ExpressionAttributeValues", Document::Object(values)),
+                ])
+            }
+            Operation::BatchGetItem => {
+                let keys = object(vec![("Keys", Document::Array(vec![key]))]);
+                object(vec![(
+                    "RequestItems",
+                    Document::Object(HashMap::from([(self.table.clone(), keys)])),
+                )])
+            }
+            Operation::BatchWriteItem => {
+                let put = object(vec![("PutRequest", object(vec![("Item", key)]))]);
+                object(vec![(
+                    "RequestItems",
+                    Document::Object(HashMap::from([(
+                        self.table.clone(),
+                        Document::Array(vec![put]),
+                    )])),
+                )])
+            }
+            Operation::TransactGetItems => object(vec![(
+                "TransactItems",
+                Document::Array(vec![object(vec![("Get", item("Key"))])]),
+            )]),
+            Operation::TransactWriteItems => object(vec![(
+                "TransactItems",
+                Document::Array(vec![object(vec![("Put", item("Item"))])]),
+            )]),
+            _ => object(vec![table()]),
+        }
+    }
+}
+
+/// What a client with `config` would decide for each of `requests`, in order, without sending
+/// any of them.
+///
+/// This is for checking a configuration against a sample workload offline, like which tables
+/// and operations it accelerates and which cache each goes to, the cache being part of the
+/// [proxy](RewriteDecision::Proxy) URI. The requests go through one fresh interceptor, so state
+/// like [warmup_requests](AcceleratorConfig::warmup_requests) and the
+/// [cost_budget](AcceleratorConfig::cost_budget) carries over from one to the next, as it would
+/// for a new client. Nothing is ever sent, so the
+/// [circuit breaker](AcceleratorConfig::circuit_breaker) never opens, and
+/// [validate_on_first_use](AcceleratorConfig::validate_on_first_use) is not checked. The
+/// requests come from a client in `us-east-1` with no per-operation settings.
+///
+/// A request that fails to be rewritten is reported as skipped with
+/// [RuntimeError](crate::SkipReason::RuntimeError), and one that is neither proxied nor skipped,
+/// like by a [custom_transform](AcceleratorConfig::custom_transform) that leaves it unchanged,
+/// as [NotRouted](crate::SkipReason::NotRouted).
+///
+/// # Example
+/// ```
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{
+///     Operation, RewriteDecision, SampleRequest, SkipReason, accelerator_config, simulate,
+/// };
+/// use std::time::Duration;
+///
+/// let config = accelerator_config()
+///     .cache_name("my-dynamo-cache")
+///     .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///     .auth_token("my-momento-auth-token")
+///     .ttl(Duration::from_secs(60))
+///     .table_cache("orders", "orders-cache")
+///     .deny_tables(["ledger".to_string()]);
+/// let key = |id: &str| [("id".to_string(), AttributeValue::S(id.into()))].into();
+///
+/// let decisions = simulate(
+///     config,
+///     [
+///         SampleRequest::new(Operation::GetItem, "users").key(key("u-1")),
+///         SampleRequest::new(Operation::Query, "orders").key(key("o-1")),
+///         SampleRequest::new(Operation::PutItem, "ledger").key(key("l-1")),
+///         SampleRequest::new(Operation::DescribeTable, "users"),
+///     ],
+/// );
+///
+/// let proxy = "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb";
+/// assert_eq!(
+///     decisions,
+///     [
+///         RewriteDecision::Proxy {
+///             uri: format!("{proxy}/my-dynamo-cache/cache"),
+///         },
+///         RewriteDecision::Proxy {
+///             uri: format!("{proxy}/orders-cache/cache"),
+///         },
+///         RewriteDecision::Skip {
+///             reason: SkipReason::DeniedTable {
+///                 table: "ledger".to_string(),
+///             },
+///         },
+///         RewriteDecision::Skip {
+///             reason: SkipReason::ControlPlane {
+///                 operation: Operation::DescribeTable,
+///             },
+///         },
+///     ],
+/// );
+/// ```
+pub fn simulate(
+    config: AcceleratorConfig,
+    requests: impl IntoIterator<Item = SampleRequest>,
+) -> Vec<RewriteDecision> {
+    let interceptor = ProxyInterceptor::for_dry_run(config);
+    requests
+        .into_iter()
+        .map(|request| match request.to_request() {
+            Ok(request) => interceptor.dry_run(request),
+            Err(error) => RewriteDecision::Skip {
+                reason: crate::SkipReason::RuntimeError {
+                    message: error.to_string(),
+                },
+            },
+        })
+        .collect()
+}
@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::Duration;
+
+use crate::{
+    AccessHint, FailMode, HostHeader, Operation, OverflowMode, Priority, ProxyMode, StateLimits,
+    TtlUnit,
+};
+
+/// A snapshot of every resolved setting of an [AcceleratorConfig](crate::AcceleratorConfig),
+/// from [effective](crate::AcceleratorConfig::effective).
+///
+/// This holds what the options add up to, like the TTL after
+/// [ttl_fraction](crate::AcceleratorConfig::ttl_fraction) and the TTL limits, or the operations
+/// left after [proxy_methods](crate::AcceleratorConfig::proxy_methods) and
+/// [enabled](crate::AcceleratorConfig::enabled). Secrets are left out: only whether an auth
+/// token is configured is recorded, and it is redacted from the proxy URI. Options that take a
+/// function, like [cache_key_fn](crate::AcceleratorConfig::cache_key_fn), are not included.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct EffectiveConfig {
+    /// The default cache
+    pub cache_name: String,
+    /// The URI accelerated requests are sent to, with the auth token redacted
+    pub proxy_uri: String,
+    /// The cache each table's requests are sent to, when not the default one
+    pub table_caches: BTreeMap<String, String>,
+    /// The caches requests are sharded across, if any
+    pub sharded_caches: Vec<String>,
+    /// The cache requests are retried against when theirs doesn't exist
+    pub fallback_cache: Option<String>,
+    /// Whether redirects from the proxy are followed
+    pub follow_proxy_redirects: bool,
+    /// The hosts besides the proxy's that redirects are followed to, in lowercase
+    pub proxy_redirect_hosts: BTreeSet<String>,
+    /// Whether the cache is named in `x-cache-name` instead of the proxy URI's path
+    pub cache_in_header: bool,
+    /// Whether the accelerator is on
+    pub enabled: bool,
+    /// The operations sent to the proxy, in the order of [Operation::ALL]
+    pub accelerated_operations: Vec<Operation>,
+    /// The tables that always go directly to DynamoDB
+    pub deny_tables: BTreeSet<String>,
+    /// The regions requests are proxied in, or `None` for every region
+    pub enabled_regions: Option<BTreeSet<String>>,
+    /// The tables whose cached responses the proxy is asked to keep resident
+    pub pin_tables: BTreeSet<String>,
+    /// Whether an auth token is configured, either directly or by a provider
+    pub has_auth_token: bool,
+    /// The query parameter the auth token is sent in, instead of a header
+    pub auth_in_query: Option<String>,
+    /// The TTL requests are cached for, with the fraction and limits applied
+    pub ttl: Duration,
+    /// The fraction of the `max_ttl` the TTL was derived from
+    pub ttl_fraction: Option<f64>,
+    /// The most each request's TTL is shortened by at random, as a fraction of it
+    pub ttl_jitter: Option<f64>,
+    /// The shortest TTL that is sent
+    pub min_ttl: Option<Duration>,
+    /// The longest TTL that is sent
+    pub max_ttl: Option<Duration>,
+    /// The header the TTL is sent in
+    pub ttl_header: String,
+    /// The unit the TTL is sent in
+    pub ttl_unit: TtlUnit,
+    /// Whether the TTL is sent as an absolute expiry time
+    pub ttl_as_absolute: bool,
+    /// How long empty responses are cached for, when not the TTL
+    pub cache_negative: Option<Duration>,
+    /// How long before expiry the proxy refreshes a cached response
+    pub refresh_ttl: Option<Duration>,
+    /// What to do when a request can't be sent to the proxy
+    pub on_runtime_error: FailMode,
+    /// The failures that open the circuit breaker, and how long it stays open
+    pub circuit_breaker: Option<(u32, Duration)>,
+    /// The most requests at the proxy at once, and what to do with the rest
+    pub max_inflight: Option<(usize, OverflowMode)>,
+    /// The extra attempts accelerated reads get
+    pub read_retries: u32,
+    /// The most failed proxied attempts an operation retries
+    pub max_proxy_retries: Option<u32>,
+    /// How long a proxied attempt has before it is retried directly against DynamoDB
+    pub hit_timeout: Option<Duration>,
+    /// How long a read waits for the proxy before it is also sent directly to DynamoDB
+    pub hedge_after: Option<Duration>,
+    /// Whether a `Retry-After` from the proxy routes around it
+    pub respect_retry_after: bool,
+    /// How many responses the local cache holds, if there is one
+    pub local_cache_capacity: Option<usize>,
+    /// How many keys the negative bloom filter is sized for, and its false positive rate
+    pub negative_bloom: Option<(usize, f64)>,
+    /// The bounds on the per-table and per-request state
+    pub state_limits: StateLimits,
+    /// Where accelerated requests are answered
+    pub proxy_mode: ProxyMode,
+    /// How the `Host` header of proxied requests is handled
+    pub host_header: HostHeader,
+    /// Whether writes invalidate the cached items they change
+    pub invalidate_on_write: bool,
+    /// Whether writes are passed through the proxy
+    pub route_all_through_proxy: bool,
+    /// Whether identical concurrent reads are coalesced at the proxy
+    pub request_coalescing: bool,
+    /// Whether BatchGetItem responses may be served partially from the cache
+    pub batch_partial_cache: bool,
+    /// Whether the proxy caches error responses
+    pub cache_errors: bool,
+    /// Whether request bodies are canonicalized before they are sent
+    pub canonical_body: bool,
+    /// Whether the item key is sent as `x-ddb-key`
+    pub emit_key_header: bool,
+    /// Whether single-item requests carry a hash of their key for sticky routing
+    pub affinity_hints: bool,
+    /// Whether each interceptor's random ID is sent as `x-client-instance`
+    pub client_instance_id: bool,
+    /// Whether the attributes a read asks for are sent as `x-requested-attributes`
+    pub key_on_attributes: bool,
+    /// Whether a read's expression attribute values are part of its `x-cache-key`
+    pub key_on_expression_values: bool,
+    /// Whether the proxy is checked to be reachable before the first proxied request
+    pub validate_on_first_use: bool,
+    /// Whether the auth token is checked for expiry before each proxied request
+    pub check_token_expiry: bool,
+    /// The priority sent for requests that don't set one
+    pub default_priority: Option<Priority>,
+    /// The access hint sent for requests that don't set one
+    pub default_access_hint: Option<AccessHint>,
+    /// The tenant sent for requests that don't set one
+    pub tenant_id: Option<String>,
+    /// The requests sent directly to DynamoDB before the proxy is used
+    pub warmup_requests: u64,
+    /// How long requests are sent directly to DynamoDB before the proxy is used
+    pub warmup_duration: Option<Duration>,
+    /// The requests per second a table needs before its requests are proxied
+    pub min_request_rate: Option<f64>,
+    /// The requests that may be proxied in each window, and the window
+    pub cost_budget: Option<(u64, Duration)>,
+    /// The version of the proxy protocol requests are sent with
+    pub proxy_protocol_version: u32,
+    /// The target this configuration's log records use
+    pub log_target: String,
+}
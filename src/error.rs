@@ -0,0 +1,84 @@
+use std::fmt::Display;
+
+/// An error in a Momento accelerator configuration, reported by [AcceleratorConfig::build](crate::AcceleratorConfig::build).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// Two options were set that contradict each other.
+    IncompatibleOptions {
+        /// The name of one of the conflicting options
+        a: &'static str,
+        /// The name of the other conflicting option
+        b: &'static str,
+    },
+    /// The proxy URI built from the configuration is not a valid URI.
+    InvalidUri {
+        /// The offending URI
+        uri: String,
+    },
+    /// The proxy URI's scheme is not `http` or `https`.
+    UnsupportedScheme {
+        /// The offending scheme
+        scheme: String,
+    },
+    /// A cache name is empty or has characters that can't be sent in the proxy URI.
+    InvalidCacheName {
+        /// The offending cache name
+        cache_name: String,
+    },
+    /// A configured header name is not a valid HTTP header name.
+    InvalidHeaderName {
+        /// The offending name
+        name: String,
+    },
+    /// An option was set to a value outside the range it accepts.
+    OutOfRange {
+        /// The name of the option
+        option: &'static str,
+        /// The offending value
+        value: String,
+        /// The accepted range
+        expected: &'static str,
+    },
+    /// An option was set without another option it depends on.
+    MissingOption {
+        /// The name of the option that was set
+        option: &'static str,
+        /// The name of the option it needs
+        requires: &'static str,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::IncompatibleOptions { a, b } => {
+                write!(f, "`{a}` cannot be combined with `{b}`")
+            }
+            ConfigError::InvalidUri { uri } => write!(f, "invalid proxy uri: {uri}"),
+            ConfigError::UnsupportedScheme { scheme } => {
+                write!(
+                    f,
+                    "unsupported proxy uri scheme `{scheme}`, expected http or https"
+                )
+            }
+            ConfigError::InvalidCacheName { cache_name } => {
+                write!(f, "invalid cache name: {cache_name:?}")
+            }
+            ConfigError::InvalidHeaderName { name } => write!(f, "invalid header name: {name:?}"),
+            ConfigError::OutOfRange {
+                option,
+                value,
+                expected,
+            } => write!(
+                f,
+                "`{option}` of {value} is out of range, expected {expected}"
+            ),
+            ConfigError::MissingOption { option, requires } => {
+                write!(f, "`{option}` requires `{requires}`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
@@ -0,0 +1,98 @@
+use aws_sdk_dynamodb::config::http::{HttpRequest, HttpResponse};
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+/// How much of a response body an [ExchangeRecord] keeps
+const MAX_RECORDED_BODY: usize = 4096;
+
+/// Request headers whose values are credentials, and are never recorded
+const REDACTED_HEADERS: [&str; 3] = [
+    "authorization",
+    "x-amz-security-token",
+    "x-momento-authorization",
+];
+
+/// A request the accelerator sent to the proxy and the response it got, recorded by
+/// [record_exchanges](crate::AcceleratorConfig::record_exchanges) for replaying offline.
+///
+/// Credentials are replaced with `redacted`: the `authorization`, `x-amz-security-token` and
+/// `x-momento-authorization` headers, and the Momento auth token wherever it appears in the
+/// URI. Available with the `record-exchanges` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ExchangeRecord {
+    /// The method of the request
+    pub method: String,
+    /// The proxy URI the request was sent to
+    pub uri: String,
+    /// The request's headers, in the order they were sent
+    pub request_headers: Vec<(String, String)>,
+    /// The response's HTTP status code
+    pub status: u16,
+    /// The response's headers
+    pub response_headers: Vec<(String, String)>,
+    /// Up to the first 4 KiB of the response body, or `None` if it was streamed
+    pub response_body: Option<Vec<u8>>,
+}
+
+/// The recorded half of an exchange, kept from when the request is sent until its response
+/// arrives
+#[derive(Debug, Clone)]
+pub(crate) struct SentRequest {
+    method: String,
+    uri: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Storable for SentRequest {
+    type Storer = StoreReplace<Self>;
+}
+
+impl SentRequest {
+    /// Record `request` without its credentials, including `auth_token` as it appears in the
+    /// URI.
+    pub(crate) fn new(request: &HttpRequest, auth_token: Option<&str>) -> Self {
+        let mut uri = request.uri().to_string();
+        if let Some(auth_token) = auth_token {
+            uri = uri.replace(auth_token, "redacted");
+        }
+        let headers = request
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if REDACTED_HEADERS
+                    .iter()
+                    .any(|redacted| name.eq_ignore_ascii_case(redacted))
+                {
+                    "redacted"
+                } else {
+                    value
+                };
+                (name.to_string(), value.to_string())
+            })
+            .collect();
+        Self {
+            method: request.method().to_string(),
+            uri,
+            headers,
+        }
+    }
+
+    /// The whole exchange, now that `response` arrived.
+    pub(crate) fn with_response(&self, response: &HttpResponse) -> ExchangeRecord {
+        ExchangeRecord {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            request_headers: self.headers.clone(),
+            status: response.status().as_u16(),
+            response_headers: response
+                .headers()
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            response_body: response
+                .body()
+                .bytes()
+                .map(|body| body[..body.len().min(MAX_RECORDED_BODY)].to_vec()),
+        }
+    }
+}
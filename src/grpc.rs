@@ -0,0 +1,464 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use aws_sdk_dynamodb::config::SharedHttpClient;
+use aws_sdk_dynamodb::config::http::{HttpRequest, HttpResponse};
+use aws_sdk_dynamodb::error::BoxError;
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_runtime_api::http::StatusCode;
+use aws_smithy_types::body::SdkBody;
+use aws_smithy_types::byte_stream::ByteStream;
+use hyper::body::HttpBody;
+
+use crate::{AcceleratorConfig, Operation, OperationInput};
+
+/// The `ECacheResult` of a Get that found the key
+const CACHE_HIT: u64 = 2;
+/// The `ECacheResult` of a Get that didn't
+const CACHE_MISS: u64 = 3;
+
+/// An HTTP client that serves DynamoDB reads from a Momento cache over gRPC, and sends every
+/// other request with the client it wraps.
+///
+/// This is for caches that speak Momento's native gRPC protocol instead of the proxy's HTTP
+/// one. It takes the place of the [interceptor](crate::MomentoAccelerator) rather than adding to
+/// it: set it as the DynamoDB client's HTTP client, wrapping the one that reaches DynamoDB.
+///
+/// Each GetItem, Query, Scan and BatchGetItem that `config`
+/// [accelerates](AcceleratorConfig::will_accelerate) is looked up with a `Get` on the
+/// `cache_client.Scs` service at the
+/// [momento_hostname](crate::AcceleratorConfigBuilder::momento_hostname),
+/// keyed by the request in a canonical form. A hit is answered with the cached response body,
+/// as DynamoDB sent it. A miss goes to DynamoDB, and a successful response is then stored with
+/// a `Set` for the configured TTL. The `cache` and `authorization` metadata carry the cache,
+/// chosen like the proxy's by [table_cache](AcceleratorConfig::table_cache) and
+/// [sharded_caches](AcceleratorConfig::sharded_caches), and the auth token.
+///
+/// Strongly consistent reads and every other operation go straight to DynamoDB. Writes don't
+/// evict cached responses, so a read can be answered with a stale one until its TTL runs out.
+/// If the cache can't be reached, the read goes to DynamoDB and a warning is logged. Options
+/// that only the proxy acts on, like its headers, don't apply.
+///
+/// Available with the `grpc` feature.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::collections::HashMap;
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// # use std::sync::{Arc, Mutex};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{GrpcTransport, accelerator_config};
+/// use std::time::Duration;
+///
+/// # // Reads a length-delimited protobuf field: its tag, its varint length, and then its bytes
+/// # fn field<'a>(message: &mut &'a [u8]) -> &'a [u8] {
+/// #     let (mut length, mut shift) = (0, 0);
+/// #     *message = &message[1..];
+/// #     loop {
+/// #         let byte = message[0];
+/// #         *message = &message[1..];
+/// #         length |= usize::from(byte & 0x7f) << shift;
+/// #         shift += 7;
+/// #         if byte < 0x80 {
+/// #             break;
+/// #         }
+/// #     }
+/// #     let (value, rest) = message.split_at(length);
+/// #     *message = rest;
+/// #     value
+/// # }
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # // A gRPC cache that keeps whatever it is sent in memory
+/// # let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+/// # let port = listener.local_addr().unwrap().port();
+/// # let cached: Arc<Mutex<HashMap<Vec<u8>, Vec<u8>>>> = Arc::default();
+/// # tokio::spawn(async move {
+/// #     while let Ok((stream, _)) = listener.accept().await {
+/// #         let cached = cached.clone();
+/// #         let service = hyper::service::service_fn(move |request: hyper::Request<hyper::Body>| {
+/// #             let cached = cached.clone();
+/// #             async move {
+/// #                 assert_eq!(request.headers()["cache"], "my-dynamo-cache");
+/// #                 assert_eq!(request.headers()["authorization"], "my-momento-auth-token");
+/// #                 let set = request.uri().path() == "/cache_client.Scs/Set";
+/// #                 let body = hyper::body::to_bytes(request.into_body()).await?;
+/// #                 let mut message = &body[5..];
+/// #                 let key = field(&mut message).to_vec();
+/// #                 let reply = if set {
+/// #                     cached.lock().unwrap().insert(key, field(&mut message).to_vec());
+/// #                     Vec::new()
+/// #                 } else if let Some(value) = cached.lock().unwrap().get(&key) {
+/// #                     // A hit, with the cached body
+/// #                     assert!(value.len() < 0x80);
+/// #                     [&[0x08, 2, 0x12, value.len() as u8], &value[..]].concat()
+/// #                 } else {
+/// #                     vec![0x08, 3]
+/// #                 };
+/// #                 let framed = [&[0][..], &(reply.len() as u32).to_be_bytes(), &reply].concat();
+/// #                 let (mut sender, body) = hyper::Body::channel();
+/// #                 tokio::spawn(async move {
+/// #                     sender.send_data(framed.into()).await.unwrap();
+/// #                     let mut trailers = hyper::HeaderMap::new();
+/// #                     trailers.insert("grpc-status", "0".parse().unwrap());
+/// #                     sender.send_trailers(trailers).await.unwrap();
+/// #                 });
+/// #                 Ok::<_, hyper::Error>(
+/// #                     hyper::Response::builder()
+/// #                         .header("content-type", "application/grpc")
+/// #                         .body(body)
+/// #                         .unwrap(),
+/// #                 )
+/// #             }
+/// #         });
+/// #         tokio::spawn(
+/// #             hyper::server::conn::Http::new()
+/// #                 .http2_only(true)
+/// #                 .serve_connection(stream, service),
+/// #         );
+/// #     }
+/// # });
+/// # let reads = Arc::new(AtomicUsize::new(0));
+/// # let dynamodb = infallible_client_fn({
+/// #     let reads = reads.clone();
+/// #     move |_| {
+/// #         reads.fetch_add(1, Ordering::Relaxed);
+/// #         http::Response::builder()
+/// #             .body(r#"{"Item": {"id": {"S": "u-1"}, "name": {"S": "Ada"}}}"#)
+/// #             .unwrap()
+/// #     }
+/// # });
+/// let accelerator = accelerator_config()
+///     .cache_name("my-dynamo-cache")
+///     .momento_hostname("127.0.0.1")
+///     .auth_token("my-momento-auth-token")
+///     .ttl(Duration::from_secs(60));
+/// # let accelerator = accelerator.port(port).insecure_http(true);
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+///     .http_client(GrpcTransport::new(accelerator, dynamodb))
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// for _ in 0..2 {
+///     let output = client
+///         .get_item()
+///         .table_name("users")
+///         .key("id", AttributeValue::S("u-1".into()))
+///         .send()
+///         .await
+///         .expect("get item");
+///     assert_eq!(output.item.unwrap()["name"], AttributeValue::S("Ada".into()));
+/// }
+/// // The first read missed and filled the cache, so only it reached DynamoDB
+/// assert_eq!(reads.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct GrpcTransport {
+    inner: SharedHttpClient,
+    cache: Arc<GrpcCache>,
+}
+
+/// Where the cache is and how long responses are kept in it, from
+/// [grpc_endpoint](AcceleratorConfig::grpc_endpoint)
+#[derive(Debug, Clone)]
+pub(crate) struct GrpcEndpoint {
+    /// The scheme, host and port of the gRPC service
+    pub(crate) origin: String,
+    pub(crate) auth_token: Option<String>,
+    pub(crate) ttl: Duration,
+}
+
+#[derive(Debug)]
+struct GrpcCache {
+    config: AcceleratorConfig,
+    endpoint: GrpcEndpoint,
+    client: hyper::Client<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
+}
+
+impl GrpcTransport {
+    /// Serve the reads `config` accelerates from its cache over gRPC, and send everything else,
+    /// including cache misses, with `inner`.
+    pub fn new(config: AcceleratorConfig, inner: impl HttpClient + 'static) -> Self {
+        let connector = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .https_or_http()
+            .enable_http2()
+            .build();
+        // gRPC is HTTP/2 only, so plain HTTP connections start with it rather than upgrading
+        let client = hyper::Client::builder().http2_only(true).build(connector);
+        Self {
+            inner: SharedHttpClient::new(inner),
+            cache: Arc::new(GrpcCache {
+                endpoint: config.grpc_endpoint(),
+                config,
+                client,
+            }),
+        }
+    }
+}
+
+impl HttpClient for GrpcTransport {
+    fn http_connector(
+        &self,
+        settings: &HttpConnectorSettings,
+        components: &RuntimeComponents,
+    ) -> SharedHttpConnector {
+        SharedHttpConnector::new(GrpcConnector {
+            inner: self.inner.http_connector(settings, components),
+            cache: self.cache.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct GrpcConnector {
+    inner: SharedHttpConnector,
+    cache: Arc<GrpcCache>,
+}
+
+impl HttpConnector for GrpcConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let (inner, cache) = (self.inner.clone(), self.cache.clone());
+        HttpConnectorFuture::new(async move { cache.serve(request, &inner).await })
+    }
+}
+
+impl GrpcCache {
+    /// Answer `request` from the cache, or else with `inner`, filling the cache on a miss.
+    async fn serve(
+        &self,
+        request: HttpRequest,
+        inner: &SharedHttpConnector,
+    ) -> Result<HttpResponse, ConnectorError> {
+        let Some((cache_name, key)) = self.cache_key(&request) else {
+            return inner.call(request).await;
+        };
+        let logs_to = self.config.logs_to();
+        match self.get(&cache_name, &key).await {
+            Ok(Some(body)) => {
+                let status = StatusCode::try_from(200).expect("200 is a valid status");
+                let mut response = HttpResponse::new(status, SdkBody::from(body));
+                response
+                    .headers_mut()
+                    .insert("content-type", "application/x-amz-json-1.0");
+                response.headers_mut().insert("x-cache-status", "hit");
+                return Ok(response);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::warn!(target: logs_to, "the gRPC cache failed to look up a read, so it goes to DynamoDB: {e}");
+                return inner.call(request).await;
+            }
+        }
+        let mut response = inner.call(request).await?;
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+        let body = ByteStream::new(response.take_body())
+            .collect()
+            .await
+            .map_err(|e| ConnectorError::io(e.into()))?
+            .into_bytes();
+        if let Err(e) = self.set(&cache_name, &key, &body).await {
+            log::warn!(target: logs_to, "the gRPC cache failed to store a response: {e}");
+        }
+        *response.body_mut() = SdkBody::from(body);
+        response.headers_mut().insert("x-cache-status", "miss");
+        Ok(response)
+    }
+
+    /// The cache and key for `request`, or `None` if it isn't a read to serve from the cache.
+    fn cache_key(&self, request: &HttpRequest) -> Option<(String, Vec<u8>)> {
+        let operation = self.config.detect_operation(request)?;
+        if !matches!(
+            operation,
+            Operation::GetItem | Operation::Query | Operation::Scan | Operation::BatchGetItem
+        ) || !self.config.will_accelerate(operation)
+        {
+            return None;
+        }
+        let input = OperationInput::from_request(request, Some(operation));
+        if input.consistent_read() {
+            return None;
+        }
+        let key = input.canonical_request()?.into_bytes();
+        Some((self.config.cache_for_input(&input).to_string(), key))
+    }
+
+    /// The cached value of `key`, or `None` on a miss.
+    async fn get(&self, cache_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>, BoxError> {
+        let response = self
+            .call("Get", cache_name, Message::default().bytes(1, key))
+            .await?;
+        let (mut result, mut body, mut message) = (0, Vec::new(), String::new());
+        for field in decode(&response).ok_or("the cache sent a malformed Get response")? {
+            match field {
+                (1, Field::Varint(value)) => result = value,
+                (2, Field::Bytes(value)) => body = value.to_vec(),
+                (3, Field::Bytes(value)) => message = String::from_utf8_lossy(value).into_owned(),
+                _ => {}
+            }
+        }
+        match result {
+            CACHE_HIT => Ok(Some(body)),
+            CACHE_MISS => Ok(None),
+            _ => Err(format!("the cache failed the Get with result {result}: {message}").into()),
+        }
+    }
+
+    /// Store `body` as the value of `key`, for the configured TTL.
+    async fn set(&self, cache_name: &str, key: &[u8], body: &[u8]) -> Result<(), BoxError> {
+        let ttl_millis = u64::try_from(self.endpoint.ttl.as_millis()).unwrap_or(u64::MAX);
+        let message = Message::default()
+            .bytes(1, key)
+            .bytes(2, body)
+            .uint64(3, ttl_millis);
+        self.call("Set", cache_name, message).await.map(|_| ())
+    }
+
+    /// Make a unary call to `method` of the cache service, returning the response message.
+    async fn call(
+        &self,
+        method: &str,
+        cache_name: &str,
+        message: Message,
+    ) -> Result<Vec<u8>, BoxError> {
+        let Message(message) = message;
+        // Each message is framed by whether it's compressed and its length
+        let mut framed = vec![0];
+        framed.extend_from_slice(&u32::try_from(message.len())?.to_be_bytes());
+        framed.extend(message);
+        let mut request = hyper::Request::post(format!(
+            "{}/cache_client.Scs/{method}",
+            self.endpoint.origin
+        ))
+        .header("content-type", "application/grpc")
+        .header("te", "trailers")
+        .header("cache", cache_name);
+        if let Some(auth_token) = &self.endpoint.auth_token {
+            request = request.header("authorization", auth_token);
+        }
+        let response = self
+            .client
+            .request(request.body(hyper::Body::from(framed))?)
+            .await?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "the cache responded to the {method} with {}",
+                response.status()
+            )
+            .into());
+        }
+        let (parts, mut body) = response.into_parts();
+        let mut data = Vec::new();
+        while let Some(chunk) = body.data().await {
+            data.extend_from_slice(&chunk?);
+        }
+        let trailers = body.trailers().await?;
+        // A response without a message can carry its status in the headers instead
+        let status = |name: &str| {
+            trailers
+                .as_ref()
+                .and_then(|trailers| trailers.get(name))
+                .or_else(|| parts.headers.get(name))
+                .and_then(|value| value.to_str().ok())
+        };
+        match status("grpc-status") {
+            Some("0") => {}
+            Some(code) => {
+                let message = status("grpc-message").unwrap_or_default();
+                return Err(format!(
+                    "the cache failed the {method} with gRPC status {code}: {message}"
+                )
+                .into());
+            }
+            None => return Err(format!("the cache's {method} response has no grpc-status").into()),
+        }
+        match data.split_first_chunk::<5>() {
+            Some(([0, length @ ..], message))
+                if message.len() == u32::from_be_bytes(*length) as usize =>
+            {
+                Ok(message.to_vec())
+            }
+            _ => Err(format!("the cache sent a malformed {method} response").into()),
+        }
+    }
+}
+
+/// A protobuf message, encoded a field at a time
+#[derive(Debug, Default)]
+struct Message(Vec<u8>);
+
+impl Message {
+    fn bytes(mut self, number: u64, value: &[u8]) -> Self {
+        self.varint(number << 3 | 2);
+        self.varint(value.len() as u64);
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    fn uint64(mut self, number: u64, value: u64) -> Self {
+        self.varint(number << 3);
+        self.varint(value);
+        self
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+}
+
+/// The value of a protobuf field
+#[derive(Debug)]
+enum Field<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+}
+
+/// The fields of a protobuf `message` by number, or `None` if it is malformed.
+///
+/// Fixed-width fields are skipped, since the cache's responses have none.
+fn decode(mut message: &[u8]) -> Option<Vec<(u64, Field<'_>)>> {
+    fn varint(message: &mut &[u8]) -> Option<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (byte, rest) = message.split_first()?;
+            *message = rest;
+            value |= u64::from(byte & 0x7f) << shift;
+            if *byte < 0x80 {
+                return Some(value);
+            }
+        }
+        None
+    }
+    let mut fields = Vec::new();
+    while !message.is_empty() {
+        let tag = varint(&mut message)?;
+        let number = tag >> 3;
+        match tag & 0x7 {
+            0 => fields.push((number, Field::Varint(varint(&mut message)?))),
+            1 => message = message.get(8..)?,
+            2 => {
+                let length = usize::try_from(varint(&mut message)?).ok()?;
+                let (value, rest) = message.split_at_checked(length)?;
+                fields.push((number, Field::Bytes(value)));
+                message = rest;
+            }
+            5 => message = message.get(4..)?,
+            _ => return None,
+        }
+    }
+    Some(fields)
+}
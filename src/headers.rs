@@ -0,0 +1,156 @@
+use std::borrow::Cow;
+
+use aws_sdk_dynamodb::config::http::HttpRequest;
+use aws_sdk_dynamodb::error::BoxError;
+
+/// A header the accelerator sends to the proxy.
+///
+/// Every accelerator header is one of these constants, so a name can't be mistyped in one place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProxyHeader(&'static str);
+
+impl ProxyHeader {
+    /// The original DynamoDB uri, for the proxy to forward the request to
+    pub(crate) const URI: Self = Self("x-uri");
+    /// The original request described as base64 JSON, see
+    /// [structured_target](crate::AcceleratorConfig::structured_target)
+    #[cfg(feature = "serde")]
+    pub(crate) const DDB_TARGET: Self = Self("x-ddb-target");
+    /// The version of the proxy protocol the request speaks, see
+    /// [proxy_protocol_version](crate::AcceleratorConfig::proxy_protocol_version)
+    pub(crate) const PROXY_PROTOCOL: Self = Self("x-momento-proxy-protocol");
+    /// The `Host` header replaced by [HostHeader::Proxy](crate::HostHeader::Proxy), or the
+    /// authority of `x-uri` with [split_original_uri](crate::AcceleratorConfig::split_original_uri)
+    pub(crate) const ORIGINAL_HOST: Self = Self("x-original-host");
+    /// The path and query of `x-uri`, see
+    /// [split_original_uri](crate::AcceleratorConfig::split_original_uri)
+    pub(crate) const ORIGINAL_PATH: Self = Self("x-original-path");
+    pub(crate) const MOMENTO_AUTHORIZATION: Self = Self("x-momento-authorization");
+    /// The cache the request is for, see
+    /// [cache_in_header](crate::AcceleratorConfig::cache_in_header)
+    pub(crate) const CACHE_NAME: Self = Self("x-cache-name");
+    pub(crate) const TTL_MILLIS: Self = Self("x-ttl-millis");
+    /// When the response expires, in epoch milliseconds, see
+    /// [ttl_as_absolute](crate::AcceleratorConfig::ttl_as_absolute)
+    pub(crate) const EXPIRE_AT_MILLIS: Self = Self("x-expire-at-millis");
+    pub(crate) const CACHE_IF_SLOWER_THAN_MILLIS: Self = Self("x-cache-if-slower-than-millis");
+    /// Tells the proxy not to cache error responses, see
+    /// [cache_errors](crate::AcceleratorConfig::cache_errors)
+    pub(crate) const NO_ERROR_CACHE: Self = Self("x-no-error-cache");
+    /// How long the proxy may cache a GetItem that found no item
+    pub(crate) const CACHE_NEGATIVE_TTL_MILLIS: Self = Self("x-cache-negative-ttl-millis");
+    /// How long a read extends a cached item's lifetime, see
+    /// [refresh_ttl](crate::AcceleratorConfig::refresh_ttl)
+    pub(crate) const REFRESH_TTL_MILLIS: Self = Self("x-refresh-ttl-millis");
+    pub(crate) const WRITE_IDEMPOTENT: Self = Self("x-write-idempotent");
+    /// `passthrough` for writes the proxy only observes, see
+    /// [route_all_through_proxy](crate::AcceleratorConfig::route_all_through_proxy)
+    pub(crate) const CACHE_MODE: Self = Self("x-cache-mode");
+    /// The TTL, cache mode and generation in one header, see
+    /// [compact_headers](crate::AcceleratorConfig::compact_headers)
+    pub(crate) const CACHE_CONTROL: Self = Self("x-momento-cache-control");
+    pub(crate) const BATCH_PARTIAL_CACHE: Self = Self("x-batch-partial-cache");
+    pub(crate) const REQUEST_HASH: Self = Self("x-request-hash");
+    /// Whether the original request was a strongly consistent read
+    pub(crate) const CONSISTENT_READ: Self = Self("x-consistent-read");
+    /// The API version from `x-amz-target`, see
+    /// [api_version_header](crate::AcceleratorConfig::api_version_header)
+    pub(crate) const API_VERSION: Self = Self("x-ddb-api-version");
+    pub(crate) const READ_REPAIR_RATE: Self = Self("x-read-repair-rate");
+    /// The key computed by [cache_key_fn](crate::AcceleratorConfig::cache_key_fn)
+    pub(crate) const CACHE_KEY: Self = Self("x-cache-key");
+    /// The percent-encoded tags of the request, comma separated, see
+    /// [cache_tags_fn](crate::AcceleratorConfig::cache_tags_fn)
+    pub(crate) const CACHE_TAGS: Self = Self("x-cache-tags");
+    /// The key attributes of each table in the request, see
+    /// [key_schema](crate::AcceleratorConfig::key_schema)
+    pub(crate) const KEY_SCHEMA: Self = Self("x-key-schema");
+    /// The normalized key of a single-item request, see
+    /// [emit_key_header](crate::AcceleratorConfig::emit_key_header)
+    pub(crate) const DDB_KEY: Self = Self("x-ddb-key");
+    /// A hash of the table and normalized key of a single-item request, see
+    /// [affinity_hints](crate::AcceleratorConfig::affinity_hints)
+    pub(crate) const AFFINITY_KEY: Self = Self("x-affinity-key");
+    /// The ID of the interceptor that sent the request, see
+    /// [client_instance_id](crate::AcceleratorConfig::client_instance_id)
+    pub(crate) const CLIENT_INSTANCE: Self = Self("x-client-instance");
+    /// The attributes the request's items are expected to have, see
+    /// [table_schema](crate::AcceleratorConfig::table_schema)
+    pub(crate) const ITEM_SCHEMA: Self = Self("x-item-schema");
+    /// The attributes a read asks for, see
+    /// [key_on_attributes](crate::AcceleratorConfig::key_on_attributes)
+    pub(crate) const REQUESTED_ATTRIBUTES: Self = Self("x-requested-attributes");
+    /// The caller's request id, see [request_id_from](crate::AcceleratorConfig::request_id_from)
+    pub(crate) const REQUEST_ID: Self = Self("x-request-id");
+    pub(crate) const CACHE_GENERATION: Self = Self("x-cache-generation");
+    /// When the request's signature expires, see
+    /// [signature_expiry_header](crate::AcceleratorConfig::signature_expiry_header)
+    pub(crate) const SIGNATURE_EXPIRY: Self = Self("x-signature-expiry");
+    /// The length of the signed request body, see
+    /// [content_length_header](crate::AcceleratorConfig::content_length_header)
+    pub(crate) const ORIGINAL_CONTENT_LENGTH: Self = Self("x-original-content-length");
+    /// Marks a request body over
+    /// [large_request_threshold](crate::AcceleratorConfig::large_request_threshold)
+    pub(crate) const LARGE_REQUEST: Self = Self("x-large-request");
+    /// A sentinel the proxy echoes back, see
+    /// [verify_headers_preserved](crate::AcceleratorConfig::verify_headers_preserved)
+    pub(crate) const HEADER_CHECK: Self = Self("x-momento-header-check");
+    /// A hash of the access key the request was signed with, see
+    /// [auth_id_header](crate::AcceleratorConfig::auth_id_header)
+    pub(crate) const AUTH_ID: Self = Self("x-auth-id");
+    /// Asks the proxy to keep the response resident, see
+    /// [pin_tables](crate::AcceleratorConfig::pin_tables)
+    pub(crate) const PIN: Self = Self("x-pin");
+    pub(crate) const IF_VERSION: Self = Self("x-if-version");
+    pub(crate) const CACHE_EVICT: Self = Self("x-cache-evict");
+    pub(crate) const EVICT_PARTITION: Self = Self("x-evict-partition");
+    /// The operation that changed the item, see [cdc_notify](crate::AcceleratorConfig::cdc_notify)
+    pub(crate) const CDC_NOTIFY: Self = Self("x-cdc-notify");
+    /// The tag to evict every cached item of, see [invalidate_by_tag](crate::invalidate_by_tag)
+    pub(crate) const EVICT_TAG: Self = Self("x-evict-tag");
+    /// How many statements a read-only BatchExecuteStatement has, see
+    /// [SkipReason::BatchHasWrites](crate::SkipReason::BatchHasWrites)
+    pub(crate) const PARTIQL_BATCH: Self = Self("x-partiql-batch");
+    pub(crate) const PRIORITY: Self = Self("x-priority");
+    pub(crate) const TENANT_ID: Self = Self("x-tenant-id");
+    /// How long the caller has left, see
+    /// [with_accelerator_deadline](crate::AcceleratorRequestExt::with_accelerator_deadline)
+    pub(crate) const DEADLINE_MILLIS: Self = Self("x-deadline-millis");
+    /// How often the request is read, see [AccessHint](crate::AccessHint)
+    pub(crate) const ACCESS_HINT: Self = Self("x-access-hint");
+
+    pub(crate) fn name(self) -> &'static str {
+        self.0
+    }
+}
+
+/// The accelerator headers for one proxied request, collected and then inserted together.
+#[derive(Debug, Default)]
+pub(crate) struct HeaderSet(Vec<(Cow<'static, str>, String)>);
+
+impl HeaderSet {
+    pub(crate) fn set(&mut self, header: ProxyHeader, value: impl Into<String>) {
+        self.0.push((Cow::Borrowed(header.name()), value.into()));
+    }
+
+    /// Set a header whose name is configured, like [ttl_header](crate::AcceleratorConfig::ttl_header).
+    pub(crate) fn set_named(&mut self, name: String, value: impl Into<String>) {
+        self.0.push((Cow::Owned(name), value.into()));
+    }
+
+    /// Insert every header into `request`.
+    ///
+    /// Values can come from the caller, like an auth token or a version, so each one is
+    /// validated. An invalid value fails the request with an error naming the header, rather
+    /// than panicking.
+    pub(crate) fn insert_into(self, request: &mut HttpRequest) -> Result<(), BoxError> {
+        for (name, value) in self.0 {
+            let error = |error| format!("invalid value for {name}: {error}");
+            request
+                .headers_mut()
+                .try_insert(name.clone(), value)
+                .map_err(error)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+/// Counts the requests currently at the proxy, up to a limit.
+#[derive(Debug)]
+pub(crate) struct Inflight {
+    limit: usize,
+    count: AtomicUsize,
+}
+
+impl Inflight {
+    pub(crate) fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Take a slot for one request, or `None` if all of them are taken.
+    pub(crate) fn try_acquire(self: &Arc<Self>) -> Option<InflightPermit> {
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < self.limit).then_some(count + 1)
+            })
+            .ok()?;
+        Some(InflightPermit(self.clone()))
+    }
+}
+
+/// A slot taken from [Inflight], given back when it is dropped.
+///
+/// It is kept in the ConfigBag for the attempt, so it is released when the attempt completes or,
+/// failing that, when the operation is dropped.
+#[derive(Debug)]
+pub(crate) struct InflightPermit(Arc<Inflight>);
+
+impl Drop for InflightPermit {
+    fn drop(&mut self) {
+        self.0.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+impl Storable for InflightPermit {
+    type Storer = StoreReplace<Self>;
+}
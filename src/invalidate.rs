@@ -0,0 +1,245 @@
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::operation::query::QueryError;
+use aws_sdk_dynamodb::operation::scan::ScanError;
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use crate::request::UpdateRequestSettings;
+use crate::{ItemKey, LOG_TARGET};
+
+/// Evict `keys` of `table_name` from the cache, without writing anything to DynamoDB.
+///
+/// Use this after changing items out of band, like with a bulk import, so readers don't see
+/// stale cached copies. `client` must be configured with
+/// [with_momento_accelerator](crate::MomentoAccelerator::with_momento_accelerator). Each key is
+/// sent to the proxy as a GetItem carrying `x-cache-evict`, which tells the proxy to drop the
+/// cached item instead of serving it. If a request skips the proxy, for example because the
+/// accelerator is disabled, it is an ordinary read and nothing is evicted. The result for each
+/// key is returned in the same order as `keys`.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config, invalidate_keys};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let evictions = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let evictions = evictions.clone();
+/// #     move |request| {
+/// #         assert_eq!(request.uri().host(), Some("api.cache.cell-us-west-2-1.prod.a.momentohq.com"));
+/// #         let headers = request.headers();
+/// #         assert_eq!(headers["x-cache-evict"], "true");
+/// #         assert_eq!(headers["x-momento-authorization"], "my-momento-auth-token");
+/// #         assert_eq!(headers["x-uri"], "https://dynamodb.us-west-2.amazonaws.com/");
+/// #         let body = String::from_utf8(request.body().bytes().unwrap().to_vec()).unwrap();
+/// #         evictions.lock().unwrap().push(body);
+/// #         http::Response::builder().body("{}").unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let keys = ["a", "b"].map(|pk| [("pk".to_string(), AttributeValue::S(pk.into()))].into());
+/// let results = invalidate_keys(&client, "my-table", keys).await;
+/// assert!(results.iter().all(Result::is_ok));
+/// # let evictions = evictions.lock().unwrap();
+/// # assert_eq!(evictions.len(), 2);
+/// # for (body, pk) in evictions.iter().zip(["a", "b"]) {
+/// #     assert!(body.contains(&format!(r#"{{"S":"{pk}"}}"#)));
+/// # }
+/// # }
+/// ```
+pub async fn invalidate_keys(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: impl Into<String>,
+    keys: impl IntoIterator<Item = ItemKey>,
+) -> Vec<Result<(), SdkError<GetItemError, HttpResponse>>> {
+    let table_name = table_name.into();
+    let mut results = Vec::new();
+    for key in keys {
+        let result = client
+            .get_item()
+            .table_name(&table_name)
+            .set_key(Some(key))
+            .customize()
+            .interceptor(UpdateRequestSettings::new(|settings| settings.evict = true))
+            .send()
+            .await;
+        if let Err(e) = &result {
+            log::debug!(target: LOG_TARGET, "failed to invalidate an item in {table_name}: {e}");
+        }
+        results.push(result.map(|_item| ()));
+    }
+    results
+}
+
+/// Evict every cached item of `table_name` in the partition where `partition_key` is `value`.
+///
+/// This is coarser than [invalidate_keys] and needs one request however many items the
+/// partition holds, which suits deleting or rewriting a whole partition. It is sent to the proxy
+/// as a Query for the partition carrying `x-evict-partition`, and the proxy evicts the cached
+/// items. As with [invalidate_keys], a request that skips the proxy is an ordinary read, limited
+/// to one item, and nothing is evicted. The [local cache](crate::AcceleratorConfig::local_cache)
+/// can't tell partitions apart, so it drops everything it holds for the table.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config, invalidate_partition};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let evictions = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let evictions = evictions.clone();
+/// #     move |request| {
+/// #         let headers = request.headers();
+/// #         assert_eq!(headers["x-evict-partition"], "true");
+/// #         assert_eq!(headers["x-amz-target"], "DynamoDB_20120810.Query");
+/// #         let body = String::from_utf8(request.body().bytes().unwrap().to_vec()).unwrap();
+/// #         evictions.lock().unwrap().push(body);
+/// #         http::Response::builder().body("{}").unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// invalidate_partition(&client, "orders", "customer", AttributeValue::S("c-42".into()))
+///     .await
+///     .expect("invalidate partition");
+/// # let evictions = evictions.lock().unwrap();
+/// # assert_eq!(evictions.len(), 1);
+/// # assert!(evictions[0].contains(r#""TableName":"orders""#));
+/// # assert!(evictions[0].contains(r#""customer""#));
+/// # assert!(evictions[0].contains(r#"{"S":"c-42"}"#));
+/// # }
+/// ```
+pub async fn invalidate_partition(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: impl Into<String>,
+    partition_key: impl Into<String>,
+    value: AttributeValue,
+) -> Result<(), SdkError<QueryError, HttpResponse>> {
+    let table_name = table_name.into();
+    let result = client
+        .query()
+        .table_name(&table_name)
+        .key_condition_expression("#pk = :pk")
+        .expression_attribute_names("#pk", partition_key)
+        .expression_attribute_values(":pk", value)
+        .limit(1)
+        .customize()
+        .interceptor(UpdateRequestSettings::new(|settings| {
+            settings.evict_partition = true
+        }))
+        .send()
+        .await;
+    if let Err(e) = &result {
+        log::debug!(target: LOG_TARGET, "failed to invalidate a partition of {table_name}: {e}");
+    }
+    result.map(|_items| ())
+}
+
+/// Evict every cached item of `table_name` that was cached with `tag`, from
+/// [cache_tags_fn](crate::AcceleratorConfig::cache_tags_fn) or
+/// [with_cache_tags](crate::AcceleratorRequestExt::with_cache_tags).
+///
+/// This groups items that share no key, like everything read for one customer. It is sent to
+/// the proxy as a Scan carrying the percent-encoded tag as `x-evict-tag`, and the proxy evicts
+/// the tagged items. As with [invalidate_partition], a request that skips the proxy is an
+/// ordinary read, limited to one item, and nothing is evicted, and the
+/// [local cache](crate::AcceleratorConfig::local_cache) drops everything it holds for the table.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config, invalidate_by_tag};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let evictions = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let evictions = evictions.clone();
+/// #     move |request| {
+/// #         let headers = request.headers();
+/// #         assert_eq!(headers["x-amz-target"], "DynamoDB_20120810.Scan");
+/// #         assert_eq!(headers.get("x-cache-tags"), None);
+/// #         evictions.lock().unwrap().push(headers["x-evict-tag"].to_owned());
+/// #         http::Response::builder().body("{}").unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// invalidate_by_tag(&client, "orders", "customer:c-42")
+///     .await
+///     .expect("invalidate by tag");
+/// # assert_eq!(*evictions.lock().unwrap(), ["customer%3Ac-42"]);
+/// # }
+/// ```
+pub async fn invalidate_by_tag(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: impl Into<String>,
+    tag: impl Into<String>,
+) -> Result<(), SdkError<ScanError, HttpResponse>> {
+    let table_name = table_name.into();
+    let tag = tag.into();
+    let result = client
+        .scan()
+        .table_name(&table_name)
+        .limit(1)
+        .customize()
+        .interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.evict_tag = Some(tag.clone())
+        }))
+        .send()
+        .await;
+    if let Err(e) = &result {
+        log::debug!(target: LOG_TARGET, "failed to invalidate a tag of {table_name}: {e}");
+    }
+    result.map(|_items| ())
+}
@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use aws_smithy_json::deserialize::json_token_iter;
+use aws_smithy_json::deserialize::token::expect_document;
+use aws_smithy_json::serialize::JsonValueWriter;
+use aws_smithy_types::{Blob, Document, base64};
+
+/// Parse a DynamoDB JSON request or response body.
+///
+/// Returns `None` for anything that isn't a single well-formed JSON value.
+pub(crate) fn parse(body: &[u8]) -> Option<Document> {
+    let mut tokens = json_token_iter(body).peekable();
+    let document = expect_document(&mut tokens).ok()?;
+    match tokens.next() {
+        None => Some(document),
+        Some(_) => None,
+    }
+}
+
+/// Look up a top level field of a JSON object.
+pub(crate) fn field<'a>(document: &'a Document, name: &str) -> Option<&'a Document> {
+    match document {
+        Document::Object(fields) => fields.get(name),
+        _ => None,
+    }
+}
+
+/// Convert an attribute value in DynamoDB's JSON form, like `{"S": "a"}`, to an [AttributeValue].
+pub(crate) fn attribute_value(document: &Document) -> Option<AttributeValue> {
+    let Document::Object(fields) = document else {
+        return None;
+    };
+    let mut fields = fields.iter();
+    let (kind, value) = fields.next()?;
+    if fields.next().is_some() {
+        return None;
+    }
+    let strings = |value: &Document| match value {
+        Document::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                Document::String(string) => Some(string.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>(),
+        _ => None,
+    };
+    let blob = |value: &str| base64::decode(value).ok().map(Blob::new);
+    Some(match (kind.as_str(), value) {
+        ("S", Document::String(string)) => AttributeValue::S(string.clone()),
+        ("N", Document::String(number)) => AttributeValue::N(number.clone()),
+        ("B", Document::String(encoded)) => AttributeValue::B(blob(encoded)?),
+        ("BOOL", Document::Bool(bool)) => AttributeValue::Bool(*bool),
+        ("NULL", Document::Bool(null)) => AttributeValue::Null(*null),
+        ("SS", value) => AttributeValue::Ss(strings(value)?),
+        ("NS", value) => AttributeValue::Ns(strings(value)?),
+        ("BS", value) => AttributeValue::Bs(
+            strings(value)?
+                .iter()
+                .map(|encoded| blob(encoded))
+                .collect::<Option<_>>()?,
+        ),
+        ("L", Document::Array(values)) => {
+            AttributeValue::L(values.iter().map(attribute_value).collect::<Option<_>>()?)
+        }
+        ("M", value) => AttributeValue::M(item(value)?),
+        _ => return None,
+    })
+}
+
+/// Convert an item or key in DynamoDB's JSON form to a map of [AttributeValue]s.
+pub(crate) fn item(document: &Document) -> Option<HashMap<String, AttributeValue>> {
+    let Document::Object(fields) = document else {
+        return None;
+    };
+    fields
+        .iter()
+        .map(|(name, value)| Some((name.clone(), attribute_value(value)?)))
+        .collect()
+}
+
+/// Convert an [AttributeValue] to DynamoDB's JSON form, or `None` for a kind this SDK doesn't
+/// know.
+pub(crate) fn attribute_value_document(value: &AttributeValue) -> Option<Document> {
+    let strings =
+        |values: &[String]| Document::Array(values.iter().cloned().map(Document::String).collect());
+    let blob = |blob: &Blob| Document::String(base64::encode(blob.as_ref()));
+    let (kind, value) = match value {
+        AttributeValue::S(string) => ("S", Document::String(string.clone())),
+        AttributeValue::N(number) => ("N", Document::String(number.clone())),
+        AttributeValue::B(value) => ("B", blob(value)),
+        AttributeValue::Bool(bool) => ("BOOL", Document::Bool(*bool)),
+        AttributeValue::Null(null) => ("NULL", Document::Bool(*null)),
+        AttributeValue::Ss(values) => ("SS", strings(values)),
+        AttributeValue::Ns(values) => ("NS", strings(values)),
+        AttributeValue::Bs(values) => ("BS", Document::Array(values.iter().map(blob).collect())),
+        AttributeValue::L(values) => (
+            "L",
+            Document::Array(
+                values
+                    .iter()
+                    .map(attribute_value_document)
+                    .collect::<Option<_>>()?,
+            ),
+        ),
+        AttributeValue::M(item) => ("M", item_document(item)?),
+        _ => return None,
+    };
+    Some(Document::Object(HashMap::from([(kind.to_string(), value)])))
+}
+
+/// Convert an item or key to DynamoDB's JSON form, see [attribute_value_document].
+pub(crate) fn item_document(item: &HashMap<String, AttributeValue>) -> Option<Document> {
+    item.iter()
+        .map(|(name, value)| Some((name.clone(), attribute_value_document(value)?)))
+        .collect::<Option<_>>()
+        .map(Document::Object)
+}
+
+/// Serialize `document`, with object keys in no particular order.
+pub(crate) fn serialize(document: &Document) -> String {
+    let mut output = String::new();
+    JsonValueWriter::new(&mut output).document(document);
+    output
+}
+
+/// Serialize `document` in a canonical form: object keys sorted, no whitespace.
+///
+/// Equivalent documents always serialize to the same bytes, whatever order their keys were in.
+pub(crate) fn canonical(document: &Document) -> String {
+    let mut output = String::new();
+    write_canonical(JsonValueWriter::new(&mut output), document);
+    output
+}
+
+/// The canonical form of an item `key`, like `{"pk":{"S":"a"},"sk":{"N":"1"}}`, or `None` if
+/// it isn't a map of attribute values.
+///
+/// On top of [canonical], numbers are normalized so that keys DynamoDB considers equal, like
+/// `1.50` and `15E-1`, serialize the same, and characters outside ASCII are escaped so the result
+/// is a valid header value.
+pub(crate) fn canonical_key(key: &Document) -> Option<String> {
+    canonical_attribute_values(key)
+}
+
+/// The canonical form of a map of attribute values, like a request's
+/// `ExpressionAttributeValues`, or `None` if it isn't one.
+///
+/// Like [canonical_key], but every value is normalized, however deeply it is nested: numbers,
+/// including those of number sets, are normalized, and the members of each set are sorted, since
+/// DynamoDB considers sets in any order equal.
+pub(crate) fn canonical_attribute_values(values: &Document) -> Option<String> {
+    let Document::Object(attributes) = values else {
+        return None;
+    };
+    let mut normalized = attributes.clone();
+    for value in normalized.values_mut() {
+        normalize_attribute_value(value)?;
+    }
+    let mut output = String::new();
+    for char in canonical(&Document::Object(normalized)).chars() {
+        if char.is_ascii() {
+            output.push(char);
+        } else {
+            for unit in char.encode_utf16(&mut [0; 2]) {
+                output.push_str(&format!("\\u{unit:04x}"));
+            }
+        }
+    }
+    Some(output)
+}
+
+/// Normalize an attribute value in DynamoDB's JSON form in place, or return `None` if it isn't
+/// one.
+fn normalize_attribute_value(value: &mut Document) -> Option<()> {
+    let Document::Object(typed) = value else {
+        return None;
+    };
+    let mut types = typed.iter_mut();
+    let (Some((data_type, value)), None) = (types.next(), types.next()) else {
+        return None;
+    };
+    match (data_type.as_str(), value) {
+        ("N", Document::String(number)) => {
+            if let Some(canonical) = normalize_number(number) {
+                *number = canonical;
+            }
+        }
+        ("NS", Document::Array(numbers)) => {
+            for number in numbers.iter_mut() {
+                if let Document::String(number) = number
+                    && let Some(canonical) = normalize_number(number)
+                {
+                    *number = canonical;
+                }
+            }
+            sort_set(numbers)?;
+        }
+        ("SS" | "BS", Document::Array(members)) => sort_set(members)?,
+        ("M", Document::Object(attributes)) => {
+            for value in attributes.values_mut() {
+                normalize_attribute_value(value)?;
+            }
+        }
+        ("L", Document::Array(values)) => {
+            for value in values.iter_mut() {
+                normalize_attribute_value(value)?;
+            }
+        }
+        ("S" | "B", Document::String(_)) | ("BOOL", Document::Bool(_)) | ("NULL", _) => {}
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Sort the string members of a set, or return `None` if one isn't a string.
+fn sort_set(members: &mut [Document]) -> Option<()> {
+    let mut strings = members
+        .iter()
+        .map(|member| match member {
+            Document::String(member) => Some(member.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    strings.sort_unstable();
+    for (member, string) in members.iter_mut().zip(strings) {
+        *member = Document::String(string);
+    }
+    Some(())
+}
+
+/// A DynamoDB number in plain decimal notation without redundant zeros, like `-0.015` for
+/// `-1.50E-2`, or `None` if it isn't a number.
+fn normalize_number(number: &str) -> Option<String> {
+    let number = number.trim();
+    let (negative, unsigned) = match number.as_bytes().first()? {
+        b'-' => (true, &number[1..]),
+        b'+' => (false, &number[1..]),
+        _ => (false, number),
+    };
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().ok()?),
+        None => (unsigned, 0),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if whole.is_empty() && fraction.is_empty()
+        || !whole
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|byte| byte.is_ascii_digit())
+    {
+        return None;
+    }
+    // The value is digits × 10^exponent
+    let mut exponent = exponent.checked_sub(fraction.len() as i64)?;
+    let digits = format!("{whole}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    let trimmed = digits.trim_end_matches('0');
+    exponent = exponent.checked_add((digits.len() - trimmed.len()) as i64)?;
+    if trimmed.is_empty() {
+        return Some("0".to_string());
+    }
+    // DynamoDB numbers have at most 38 digits and exponents within ±130
+    if !(-200..=200).contains(&exponent) {
+        return None;
+    }
+    let plain = if exponent >= 0 {
+        format!("{trimmed}{}", "0".repeat(exponent as usize))
+    } else {
+        let point = trimmed.len() as i64 + exponent;
+        if point > 0 {
+            format!(
+                "{}.{}",
+                &trimmed[..point as usize],
+                &trimmed[point as usize..]
+            )
+        } else {
+            format!("0.{}{trimmed}", "0".repeat(-point as usize))
+        }
+    };
+    Some(if negative { format!("-{plain}") } else { plain })
+}
+
+fn write_canonical(writer: JsonValueWriter<'_>, document: &Document) {
+    match document {
+        Document::Object(fields) => {
+            let mut fields: Vec<_> = fields.iter().collect();
+            fields.sort_unstable_by_key(|(name, _)| name.as_str());
+            let mut object = writer.start_object();
+            for (name, value) in fields {
+                write_canonical(object.key(name), value);
+            }
+            object.finish();
+        }
+        Document::Array(values) => {
+            let mut array = writer.start_array();
+            for value in values {
+                write_canonical(array.value(), value);
+            }
+            array.finish();
+        }
+        scalar => writer.document(scalar),
+    }
+}
@@ -21,10 +21,186 @@
 //!
 //! let dynamodb_client = aws_sdk_dynamodb::Client::from_conf(dynamodb_config);
 //! ```
+//!
+//! # DynamoDB Local
+//! When developing against DynamoDB Local, point the accelerator at a local proxy with
+//! [insecure_http](AcceleratorConfig::insecure_http), [port](AcceleratorConfig::port), and
+//! [path_template](AcceleratorConfig::path_template). The proxy still receives the original
+//! DynamoDB Local URI in `x-uri`. To skip the proxy entirely, use
+//! [enabled(false)](AcceleratorConfig::enabled) and requests go straight to DynamoDB Local.
+//! ```rust
+//! # use momento_ddb_interceptor::test_support::TestDefaults;
+//! # use aws_smithy_http_client::test_util::capture_request;
+//! use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config};
+//! use std::time::Duration;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let local_accelerator = |enabled| {
+//!     accelerator_config()
+//!         .cache_name("local-cache")
+//!         .momento_hostname("localhost")
+//!         .auth_token("local-token")
+//!         .ttl(Duration::from_secs(60))
+//!         .insecure_http(true)
+//!         .port(4566)
+//!         .path_template("/ddb/{cache}")
+//!         .enabled(enabled)
+//! };
+//!
+//! for (enabled, expected_uri) in [
+//!     (true, "http://localhost:4566/ddb/local-cache"),
+//!     (false, "http://localhost:8000/"),
+//! ] {
+//! #   let (http_client, request) = capture_request(None);
+//!     let config = aws_sdk_dynamodb::Config::builder()
+//! #       .test_defaults()
+//! #       .http_client(http_client)
+//!         .endpoint_url("http://localhost:8000")
+//!         .with_momento_accelerator(local_accelerator(enabled))
+//!         .build();
+//!     let client = aws_sdk_dynamodb::Client::from_conf(config);
+//!     let _ = client.get_item().table_name("my-table").send().await;
+//!
+//!     let request = request.expect_request();
+//!     assert_eq!(request.uri(), expected_uri);
+//!     if enabled {
+//!         assert_eq!(request.headers().get("x-uri"), Some("http://localhost:8000/"));
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! # Testing
+//! The accelerator runs inside the SDK's request pipeline, so the simplest way to test a
+//! configuration is a real client with a capturing HTTP client from
+//! `aws_smithy_http_client::test_util` (enable its `test-util` feature in your dev-dependencies).
+//! No network is needed, and the captured request shows exactly what the proxy would receive.
+//! ```rust
+//! # use aws_sdk_dynamodb::config::{BehaviorVersion, Credentials, Region};
+//! use aws_smithy_http_client::test_util::capture_request;
+//! use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config};
+//! use std::time::Duration;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let (http_client, request) = capture_request(None);
+//! let config = aws_sdk_dynamodb::Config::builder()
+//!     .behavior_version(BehaviorVersion::latest())
+//!     .region(Region::new("us-west-2"))
+//!     .credentials_provider(Credentials::new("akid", "secret", None, None, "test"))
+//!     .http_client(http_client)
+//!     .with_momento_accelerator(
+//!         accelerator_config()
+//!             .cache_name("my-dynamo-cache")
+//!             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+//!             .auth_token("my-momento-auth-token")
+//!             .ttl(Duration::from_secs(60)),
+//!     )
+//!     .build();
+//! let client = aws_sdk_dynamodb::Client::from_conf(config);
+//! let _ = client.get_item().table_name("my-table").send().await;
+//!
+//! let request = request.expect_request();
+//! assert_eq!(
+//!     request.uri(),
+//!     "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache",
+//! );
+//! assert_eq!(
+//!     request.headers().get("x-uri"),
+//!     Some("https://dynamodb.us-west-2.amazonaws.com/"),
+//! );
+//! assert_eq!(
+//!     request.headers().get("x-momento-authorization"),
+//!     Some("my-momento-auth-token"),
+//! );
+//! assert_eq!(request.headers().get("x-ttl-millis"), Some("60000"));
+//! # }
+//! ```
 
+mod accelerated_client;
+mod backoff;
+mod batch;
+mod circuit_breaker;
+mod clock;
+mod cost_budget;
+mod curl;
+mod decision;
+mod dry_run;
+mod effective;
+mod error;
+#[cfg(feature = "record-exchanges")]
+mod exchange;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod headers;
+mod inflight;
+mod invalidate;
+mod json;
+mod local_cache;
+mod metrics;
+mod negative_bloom;
+mod operation;
+mod operation_input;
+#[cfg(feature = "otel")]
+mod otel;
+mod overload;
+mod prefetch;
+mod projection;
+mod proxy_error;
 mod proxy_interceptor;
+#[cfg(feature = "test-util")]
+mod recorder;
+mod redirect;
+mod request;
+mod response;
+mod routes;
+mod schema;
+#[cfg(feature = "simulated")]
+mod simulated;
+mod skip_reason;
+mod stats;
+mod table_selection;
+#[doc(hidden)]
+pub mod test_support;
+#[cfg(feature = "tls13")]
+mod tls;
+mod token;
+#[cfg(feature = "tracing")]
+mod trace;
 
+pub use accelerated_client::{AcceleratedDynamoClient, Cached};
+pub use backoff::Jitter;
+pub use batch::chunked_batch_get_item;
+pub use clock::{Clock, Rng, SystemClock, SystemRng};
+pub use decision::RewriteDecision;
+pub use dry_run::{SampleRequest, simulate};
+pub use effective::EffectiveConfig;
+pub use error::ConfigError;
+#[cfg(feature = "record-exchanges")]
+pub use exchange::ExchangeRecord;
+#[cfg(feature = "grpc")]
+pub use grpc::GrpcTransport;
+pub use invalidate::{invalidate_by_tag, invalidate_keys, invalidate_partition};
+pub use metrics::AcceleratorMetrics;
+pub use operation::Operation;
+pub use operation_input::OperationInput;
+#[cfg(feature = "otel")]
+pub use otel::OtelMetrics;
+pub use prefetch::{ItemKey, prefetch};
+pub use proxy_error::ProxyError;
 pub use proxy_interceptor::{
-    AcceleratorConfig, AcceleratorConfigBuilder, MomentoAccelerator, ProxyInterceptor,
-    accelerator_config,
+    AcceleratorConfig, AcceleratorConfigBuilder, AcceleratorDefaults, AccessHint, FailMode,
+    HostHeader, LOG_TARGET, MomentoAccelerator, OverflowMode, PROXY_PROTOCOL_VERSION, Priority,
+    ProxyInterceptor, ProxyMode, TtlAdjustment, TtlUnit, accelerator_config,
+    accelerator_config_with_defaults, validate_configs,
 };
+#[cfg(feature = "test-util")]
+pub use recorder::{DecisionRecorder, RecordedDecision};
+pub use request::{AcceleratorRequestExt, RequestOverrides};
+pub use response::{AcceleratorResponse, AcceleratorStatus, CacheStatus};
+pub use routes::DisabledRoutes;
+pub use schema::{AttributeType, SchemaDescriptor};
+pub use skip_reason::SkipReason;
+pub use stats::{AcceleratorStats, StateLimits};
+pub use table_selection::TableSelection;
@@ -0,0 +1,316 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use aws_sdk_dynamodb::operation::get_item::GetItemOutput;
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+
+use crate::operation_input::OperationInput;
+use crate::request::RequestSettings;
+use crate::{AcceleratorConfig, Operation, ProxyInterceptor};
+
+/// A small in-process LRU of GetItem responses, checked before the proxy.
+#[derive(Debug)]
+pub(crate) struct LocalCache {
+    capacity: usize,
+    state: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, Entry>,
+    /// The key of each entry by its `last_used`, least recent first
+    recency: BTreeMap<u64, String>,
+    /// Incremented on every use, so the entry with the lowest `last_used` is the least recent
+    uses: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    table: String,
+    output: GetItemOutput,
+    expires_at: SystemTime,
+    last_used: u64,
+}
+
+/// What the local cache is doing for the current attempt
+#[derive(Debug, Clone)]
+pub(crate) enum LocalCacheAttempt {
+    /// The attempt doesn't involve the local cache
+    None,
+    /// The response was found locally and the request was not sent
+    Hit(Box<GetItemOutput>),
+    /// The response should be cached locally once it arrives
+    Fill {
+        key: String,
+        table: String,
+        expires_at: SystemTime,
+    },
+}
+
+impl Storable for LocalCacheAttempt {
+    type Storer = StoreReplace<Self>;
+}
+
+impl LocalCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Default::default(),
+        }
+    }
+
+    /// The cached response for `key`, unless it has expired by `now`.
+    pub(crate) fn get(&self, key: &str, now: SystemTime) -> Option<GetItemOutput> {
+        let mut state = self.state();
+        state.uses += 1;
+        let uses = state.uses;
+        let entry = state.entries.get(key)?;
+        if entry.expires_at <= now {
+            state.remove(key);
+            return None;
+        }
+        let output = entry.output.clone();
+        state.touch(key, uses);
+        Some(output)
+    }
+
+    pub(crate) fn insert(
+        &self,
+        key: String,
+        table: String,
+        output: GetItemOutput,
+        expires_at: SystemTime,
+    ) {
+        let mut state = self.state();
+        state.uses += 1;
+        let last_used = state.uses;
+        state.remove(&key);
+        if self.capacity <= state.entries.len()
+            && let Some((_, least_recent)) = state.recency.pop_first()
+        {
+            state.entries.remove(&least_recent);
+        }
+        state.recency.insert(last_used, key.clone());
+        state.entries.insert(
+            key,
+            Entry {
+                table,
+                output,
+                expires_at,
+                last_used,
+            },
+        );
+    }
+
+    pub(crate) fn remove(&self, key: &str) {
+        self.state().remove(key);
+    }
+
+    /// Drop every cached response from `table`.
+    pub(crate) fn invalidate_table(&self, table: &str) {
+        let mut state = self.state();
+        let CacheState {
+            entries, recency, ..
+        } = &mut *state;
+        entries.retain(|_, entry| {
+            let keep = entry.table != table;
+            if !keep {
+                recency.remove(&entry.last_used);
+            }
+            keep
+        });
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl CacheState {
+    /// Mark the entry for `key` as used by use number `uses`.
+    fn touch(&mut self, key: &str, uses: u64) {
+        if let Some(entry) = self.entries.get_mut(key) {
+            let key = self
+                .recency
+                .remove(&entry.last_used)
+                .expect("every entry has a use");
+            entry.last_used = uses;
+            self.recency.insert(uses, key);
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+}
+
+/// The error that stops a request from being sent when it was answered from the local cache.
+///
+/// It never reaches the caller: the cached response replaces it before the attempt completes.
+#[derive(Debug)]
+pub(crate) struct LocalCacheHit;
+
+impl std::fmt::Display for LocalCacheHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("served from the local cache")
+    }
+}
+
+impl std::error::Error for LocalCacheHit {}
+
+impl AcceleratorConfig {
+    /// Keep up to `capacity` GetItem responses in memory, and answer repeated reads from there
+    /// without sending them at all.
+    ///
+    /// This saves the proxy round trip for very hot keys. A response is kept for the same TTL
+    /// the proxy was given for it, and the least recently used one is dropped when the cache is
+    /// full. Strongly consistent reads always go out, and a write or any other operation that
+    /// is not a read drops the cached responses for the tables it names. Since this cache is
+    /// per process, other processes' writes are only seen once the TTL runs out.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::types::AttributeValue;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{Clock, MomentoAccelerator, accelerator_config};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct FakeClock(Arc<Mutex<SystemTime>>);
+    /// impl Clock for FakeClock {
+    ///     fn now(&self) -> SystemTime {
+    ///         *self.0.lock().unwrap()
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let sent = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let sent = sent.clone();
+    /// #     move |request| {
+    /// #         sent.lock().unwrap().push(request.uri().host().unwrap_or_default().to_string());
+    /// #         http::Response::builder()
+    /// #             .body(r#"{"Item":{"pk":{"S":"user#42"},"name":{"S":"Ferris"}}}"#)
+    /// #             .unwrap()
+    /// #     }
+    /// # });
+    /// let clock = FakeClock(Arc::new(Mutex::new(SystemTime::UNIX_EPOCH)));
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .local_cache(100)
+    ///             .clock(clock.clone()),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let get_item = |pk: &str| {
+    ///     client
+    ///         .get_item()
+    ///         .table_name("my-table")
+    ///         .key("pk", AttributeValue::S(pk.into()))
+    ///         .send()
+    /// };
+    ///
+    /// // The first read goes to the proxy, and the second is answered locally
+    /// get_item("user#42").await.expect("get item");
+    /// let item = get_item("user#42").await.expect("get item").item.expect("item");
+    /// assert_eq!(item["name"], AttributeValue::S("Ferris".into()));
+    /// # assert_eq!(sent.lock().unwrap().len(), 1);
+    ///
+    /// // A different key isn't cached locally yet, so it falls through to the proxy
+    /// get_item("user#7").await.expect("get item");
+    /// # assert_eq!(
+    /// #     *sent.lock().unwrap(),
+    /// #     ["api.cache.cell-us-west-2-1.prod.a.momentohq.com"; 2],
+    /// # );
+    ///
+    /// // Once the TTL has passed, the proxy is asked again
+    /// *clock.0.lock().unwrap() += Duration::from_secs(59);
+    /// get_item("user#42").await.expect("get item");
+    /// # assert_eq!(sent.lock().unwrap().len(), 2);
+    /// *clock.0.lock().unwrap() += Duration::from_secs(1);
+    /// get_item("user#42").await.expect("get item");
+    /// # assert_eq!(sent.lock().unwrap().len(), 3);
+    /// # }
+    /// ```
+    pub fn local_cache(mut self, capacity: usize) -> Self {
+        self.local_cache_capacity = Some(capacity);
+        self
+    }
+}
+
+impl ProxyInterceptor {
+    /// Decide how the local cache takes part in this attempt, or `None` if it doesn't.
+    pub(crate) fn local_cache_lookup(
+        &self,
+        local_cache: &LocalCache,
+        operation: Option<Operation>,
+        input: &OperationInput,
+        cfg: &ConfigBag,
+    ) -> Option<LocalCacheAttempt> {
+        if operation != Some(Operation::GetItem) {
+            // Anything else might change the tables it touches, and the local cache can't tell
+            // which of its items are in a partition or have a tag
+            let evict_group = cfg
+                .load::<RequestSettings>()
+                .is_some_and(|settings| settings.evict_partition || settings.evict_tag.is_some());
+            if evict_group || !operation.is_some_and(|operation| operation.is_item_read()) {
+                for table in input.table_names() {
+                    local_cache.invalidate_table(table);
+                }
+            }
+            return None;
+        }
+        let key = self.local_key(input, cfg)?;
+        if cfg
+            .load::<RequestSettings>()
+            .is_some_and(|settings| settings.evict)
+        {
+            local_cache.remove(&key);
+            return None;
+        }
+        // A strongly consistent read has to see the latest write
+        if input.consistent_read() {
+            return None;
+        }
+        let now = self.config.clock.now();
+        if let Some(output) = local_cache.get(&key, now) {
+            return Some(LocalCacheAttempt::Hit(Box::new(output)));
+        }
+        Some(LocalCacheAttempt::Fill {
+            key,
+            table: input.table_names().first()?.to_string(),
+            // The local copy never outlives the proxy's
+            expires_at: now + self.ttl_for(input, cfg),
+        })
+    }
+
+    /// What a request is remembered by in memory, by the local cache and the negative bloom
+    /// filter.
+    pub(crate) fn local_key(&self, input: &OperationInput, cfg: &ConfigBag) -> Option<String> {
+        // Not the request hash: two requests whose hashes collide would get each other's item
+        let mut key = input.canonical_request()?;
+        // The same request to another tenant or cache can have a different response. Each name
+        // is prefixed with its length, so it can't run into what follows it.
+        let settings = cfg.load::<RequestSettings>();
+        if let Some(tenant_id) = self.config.tenant_for(settings) {
+            key = format!("tenant {}:{tenant_id}{key}", tenant_id.len());
+        }
+        if let Some(cache_name) = settings.and_then(|settings| settings.cache.as_deref()) {
+            key = format!("cache {}:{cache_name}{key}", cache_name.len());
+        }
+        Some(key)
+    }
+}
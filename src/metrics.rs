@@ -0,0 +1,129 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::{Operation, SkipReason};
+
+/// Receives measurements of how the accelerator is handling requests.
+///
+/// Attach an implementation with [AcceleratorConfig::metrics](crate::AcceleratorConfig::metrics)
+/// to forward them to your metrics system. Every method does nothing by default, so implement
+/// only the ones you need. They are called on the request path, so keep them cheap.
+///
+/// The proxy reports whether it served a request from the cache in the `x-cache-status`
+/// response header, as `hit` or `miss`. Responses from the
+/// [local cache](crate::AcceleratorConfig::local_cache) count as hits. The operation is `None`
+/// when the request's `x-amz-target` header didn't name one this crate knows.
+///
+/// A request that skips the proxy is a [bypass](Self::bypass), and one the proxy failed to
+/// answer is [proxy_unavailable](Self::proxy_unavailable), so neither is counted as a miss.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// use momento_ddb_interceptor::{
+///     AcceleratorMetrics, MomentoAccelerator, Operation, SkipReason, accelerator_config,
+/// };
+/// use std::sync::atomic::{AtomicU64, Ordering};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// #[derive(Debug, Default)]
+/// struct Counts {
+///     hits: AtomicU64,
+///     misses: AtomicU64,
+///     bypasses: AtomicU64,
+///     unavailable: AtomicU64,
+/// }
+///
+/// #[derive(Debug, Clone, Default)]
+/// struct CountingMetrics(Arc<Counts>);
+///
+/// impl AcceleratorMetrics for CountingMetrics {
+///     fn cache_hit(&self, _operation: Option<Operation>) {
+///         self.0.hits.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn cache_miss(&self, _operation: Option<Operation>) {
+///         self.0.misses.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn bypass(&self, _operation: Option<Operation>, _reason: &SkipReason) {
+///         self.0.bypasses.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn proxy_unavailable(&self, _operation: Option<Operation>) {
+///         self.0.unavailable.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let http_client = infallible_client_fn(|request| {
+/// #     let target = &request.headers()["x-amz-target"];
+/// #     if target == "DynamoDB_20120810.Scan" {
+/// #         return http::Response::builder().status(503).body("{}").unwrap();
+/// #     }
+/// #     let get_item = target == "DynamoDB_20120810.GetItem";
+/// #     let status = if get_item { "hit" } else { "miss" };
+/// #     http::Response::builder()
+/// #         .header("x-cache-status", status)
+/// #         .body("{}")
+/// #         .unwrap()
+/// # });
+/// let metrics = CountingMetrics::default();
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+/// #   .retry_config(RetryConfig::disabled())
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60))
+///             .deny_tables(["audit-log".to_string()])
+///             .metrics(metrics.clone()),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// // The proxy in this example has every item cached, runs every query and fails every scan
+/// let _ = client.get_item().table_name("my-table").send().await;
+/// let _ = client.query().table_name("my-table").send().await;
+/// let _ = client.scan().table_name("my-table").send().await;
+/// let _ = client.get_item().table_name("audit-log").send().await;
+///
+/// assert_eq!(metrics.0.hits.load(Ordering::Relaxed), 1);
+/// assert_eq!(metrics.0.misses.load(Ordering::Relaxed), 1);
+/// assert_eq!(metrics.0.unavailable.load(Ordering::Relaxed), 1);
+/// assert_eq!(metrics.0.bypasses.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+// The defaults ignore their arguments, but the names document the trait
+#[allow(unused_variables)]
+pub trait AcceleratorMetrics: Debug + Send + Sync {
+    /// A response was served from the cache.
+    fn cache_hit(&self, operation: Option<Operation>) {}
+
+    /// The proxy had to read the response from DynamoDB.
+    fn cache_miss(&self, operation: Option<Operation>) {}
+
+    /// The proxy responded `latency` after the request was sent to it.
+    ///
+    /// This is measured with the configured [Clock](crate::Clock), once per attempt.
+    fn proxy_latency(&self, operation: Option<Operation>, latency: Duration) {}
+
+    /// A request was sent directly to DynamoDB instead of the proxy.
+    fn bypass(&self, operation: Option<Operation>, reason: &SkipReason) {}
+
+    /// A request sent to the proxy got no response, or a server error.
+    ///
+    /// This is called once per failed attempt, so a retried request can report it more than once.
+    fn proxy_unavailable(&self, operation: Option<Operation>) {}
+
+    /// Send any measurements buffered so far, because the accelerator is
+    /// [shutting down](crate::ProxyInterceptor::shutdown).
+    fn flush(&self) {}
+}
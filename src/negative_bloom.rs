@@ -0,0 +1,295 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use aws_smithy_types::config_bag::{ConfigBag, Storable, StoreReplace};
+
+use crate::operation_input::{Fnv1a, OperationInput};
+use crate::request::RequestSettings;
+use crate::{AcceleratorConfig, Operation, ProxyInterceptor};
+
+/// The fraction of reads [AcceleratorConfig::negative_bloom] would answer that are sent anyway,
+/// to catch items it wrongly reports absent
+const NEGATIVE_BLOOM_VERIFY_RATE: f64 = 0.01;
+
+/// The false positive rates a filter is sized for, whatever it was asked for
+const FP_RATES: (f64, f64) = (1e-9, 0.5);
+
+/// The most bits a filter has, 128 MiB, so a large capacity can't exhaust memory
+const MAX_BITS: u64 = 1 << 30;
+
+/// A bloom filter of the GetItem requests DynamoDB found no item for, checked before the proxy.
+///
+/// A bloom filter can't forget a single key, so the whole filter is cleared whenever one of its
+/// keys may have been written, and once a TTL has passed since it was last cleared.
+#[derive(Debug)]
+pub(crate) struct NegativeBloom {
+    filter: Mutex<Filter>,
+    bit_count: u64,
+    hashes: u32,
+}
+
+#[derive(Debug)]
+struct Filter {
+    bits: Vec<u64>,
+    /// When the keys in the filter started being added
+    since: SystemTime,
+}
+
+/// What the negative bloom filter is doing for the current attempt
+#[derive(Debug, Clone)]
+pub(crate) enum NegativeBloomAttempt {
+    /// The attempt doesn't involve the filter
+    None,
+    /// The filter says the item is absent, and the request was not sent
+    Absent,
+    /// The response decides whether the request with `key` is added to the filter, or, if the
+    /// filter already had it, whether the filter was wrong
+    Check { key: String, known_absent: bool },
+}
+
+impl Storable for NegativeBloomAttempt {
+    type Storer = StoreReplace<Self>;
+}
+
+impl NegativeBloom {
+    /// A filter sized to hold `capacity` keys while reporting a key it doesn't have with
+    /// probability `fp_rate`, empty as of `now`.
+    ///
+    /// A rate outside of [FP_RATES] is taken as the nearest one in it, and the size is capped at
+    /// [MAX_BITS], so that no arguments can make this abort.
+    pub(crate) fn new(capacity: usize, fp_rate: f64, now: SystemTime) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let fp_rate = match fp_rate {
+            fp_rate if fp_rate.is_nan() => FP_RATES.1,
+            fp_rate => fp_rate.clamp(FP_RATES.0, FP_RATES.1),
+        };
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = (-capacity * fp_rate.ln() / (ln2 * ln2))
+            .ceil()
+            .clamp(64.0, MAX_BITS as f64) as u64;
+        let hashes = (bit_count as f64 / capacity * ln2).round().clamp(1.0, 32.0) as u32;
+        Self {
+            filter: Mutex::new(Filter {
+                bits: vec![0; bit_count.div_ceil(64) as usize],
+                since: now,
+            }),
+            bit_count,
+            hashes,
+        }
+    }
+
+    /// Whether `key` was inserted, or is a false positive.
+    pub(crate) fn contains(&self, key: &str) -> bool {
+        let filter = self.filter();
+        self.positions(key)
+            .all(|position| filter.bits[(position / 64) as usize] & (1 << (position % 64)) != 0)
+    }
+
+    pub(crate) fn insert(&self, key: &str) {
+        let mut filter = self.filter();
+        for position in self.positions(key) {
+            filter.bits[(position / 64) as usize] |= 1 << (position % 64);
+        }
+    }
+
+    /// Forget every key.
+    pub(crate) fn clear(&self) {
+        self.filter().bits.fill(0);
+    }
+
+    /// Forget every key if the oldest may have been added `ttl` or more before `now`, so no key
+    /// is known absent for longer than that.
+    pub(crate) fn expire(&self, now: SystemTime, ttl: Duration) {
+        let mut filter = self.filter();
+        if now.duration_since(filter.since).unwrap_or_default() >= ttl {
+            filter.bits.fill(0);
+            filter.since = now;
+        }
+    }
+
+    /// The bits of `key`, by double hashing.
+    fn positions(&self, key: &str) -> impl Iterator<Item = u64> {
+        let mut hasher = Fnv1a::default();
+        hasher.write(key.as_bytes());
+        let first = hasher.0;
+        hasher.write(&[0xff]);
+        // Never zero, so the hashes don't all pick the same bit
+        let second = hasher.0 | 1;
+        let bit_count = self.bit_count;
+        (0..u64::from(self.hashes))
+            .map(move |i| first.wrapping_add(i.wrapping_mul(second)) % bit_count)
+    }
+
+    fn filter(&self) -> std::sync::MutexGuard<'_, Filter> {
+        self.filter.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The error that stops a request from being sent when the filter says its item is absent.
+///
+/// It never reaches the caller: an empty GetItem output replaces it before the attempt completes.
+#[derive(Debug)]
+pub(crate) struct KnownAbsent;
+
+impl std::fmt::Display for KnownAbsent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the item is known to be absent")
+    }
+}
+
+impl std::error::Error for KnownAbsent {}
+
+impl AcceleratorConfig {
+    /// Remember the GetItem requests DynamoDB found no item for in a bloom filter, and answer
+    /// them again with no item without sending them at all.
+    ///
+    /// This is for workloads dominated by reads of keys that don't exist, which otherwise each cost
+    /// a round trip to the proxy. The filter is sized for `capacity` keys and reports a key it
+    /// doesn't have with probability `fp_rate`, which must be between 0 and 1 exclusive, or
+    /// [build](Self::build) reports a [ConfigError::OutOfRange](crate::ConfigError::OutOfRange).
+    /// Without building, a rate outside of that is taken as the nearest of `1e-9` and `0.5`. Past
+    /// `capacity` keys, false positives become more likely. The filter never takes more than 128
+    /// MiB, so a capacity that would need more gets more false positives instead.
+    ///
+    /// A read that is a false positive, or of an item written since, would wrongly find no
+    /// item. To catch those, 1% of the reads the filter would answer are sent anyway, and if
+    /// one finds its item, every key is forgotten. A write through the interceptor, or an
+    /// [invalidation](crate::invalidate_keys), may create an item, so it also clears the
+    /// filter. Strongly consistent reads never use it. The filter is shared by every client that
+    /// uses the same interceptor, and which reads are verified comes from the [rng](Self::rng).
+    ///
+    /// An item created by another writer is still missed until it is verified, or at most one
+    /// [ttl](crate::AcceleratorConfigBuilder::ttl) after its key was first found absent: the whole
+    /// filter is cleared once that long has passed since it was last cleared, as told by the
+    /// [clock](Self::clock). This is the TTL the interceptor currently uses, which
+    /// [set_ttl](crate::ProxyInterceptor::set_ttl) changes, not the TTL of any single request.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    /// # use std::sync::{Arc, Mutex};
+    /// use aws_sdk_dynamodb::types::AttributeValue;
+    /// use momento_ddb_interceptor::{Clock, MomentoAccelerator, Rng, accelerator_config};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// /// Random numbers from a fixed sequence
+    /// #[derive(Debug, Clone)]
+    /// struct FixedRng(Arc<Mutex<Vec<f64>>>);
+    ///
+    /// impl Rng for FixedRng {
+    ///     fn next_f64(&self) -> f64 {
+    ///         self.0.lock().unwrap().remove(0)
+    ///     }
+    /// }
+    ///
+    /// /// A clock that only moves when told to
+    /// #[derive(Debug, Clone)]
+    /// struct FakeClock(Arc<Mutex<SystemTime>>);
+    ///
+    /// impl Clock for FakeClock {
+    ///     fn now(&self) -> SystemTime {
+    ///         *self.0.lock().unwrap()
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let (sent, exists) = (Arc::new(AtomicU32::new(0)), Arc::new(AtomicBool::new(false)));
+    /// # let http_client = infallible_client_fn({
+    /// #     let (sent, exists) = (sent.clone(), exists.clone());
+    /// #     move |_| {
+    /// #         sent.fetch_add(1, Ordering::Relaxed);
+    /// #         let body = if exists.load(Ordering::Relaxed) {
+    /// #             r#"{"Item": {"pk": {"S": "user#42"}}}"#
+    /// #         } else {
+    /// #             "{}"
+    /// #         };
+    /// #         http::Response::builder().body(body).unwrap()
+    /// #     }
+    /// # });
+    /// // Don't verify the second read, verify the third
+    /// let rng = FixedRng(Arc::new(Mutex::new(vec![0.5, 0.0])));
+    /// let clock = FakeClock(Arc::new(Mutex::new(SystemTime::UNIX_EPOCH)));
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .negative_bloom(10_000, 0.001)
+    ///             .rng(rng)
+    ///             .clock(clock.clone()),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let get_item = async || {
+    ///     let output = client
+    ///         .get_item()
+    ///         .table_name("my-table")
+    ///         .key("pk", AttributeValue::S("user#42".into()))
+    ///         .send()
+    ///         .await
+    ///         .expect("get item");
+    ///     output.item
+    /// };
+    ///
+    /// // The first read finds no item, so the second isn't sent
+    /// assert_eq!(get_item().await, None);
+    /// assert_eq!(get_item().await, None);
+    /// # assert_eq!(sent.load(Ordering::Relaxed), 1);
+    ///
+    /// // Once the TTL has passed, the filter is cleared and the read is sent again
+    /// *clock.0.lock().unwrap() += Duration::from_secs(60);
+    /// assert_eq!(get_item().await, None);
+    /// # assert_eq!(sent.load(Ordering::Relaxed), 2);
+    ///
+    /// // Once the item exists, a verified read finds it and clears the filter
+    /// # exists.store(true, Ordering::Relaxed);
+    /// assert!(get_item().await.is_some());
+    /// # assert_eq!(sent.load(Ordering::Relaxed), 3);
+    /// assert!(get_item().await.is_some());
+    /// # assert_eq!(sent.load(Ordering::Relaxed), 4);
+    /// # }
+    /// ```
+    pub fn negative_bloom(mut self, capacity: usize, fp_rate: f64) -> Self {
+        self.negative_bloom = Some((capacity, fp_rate));
+        self
+    }
+}
+
+impl ProxyInterceptor {
+    /// Decide how the negative bloom filter takes part in this attempt, or `None` if it doesn't.
+    pub(crate) fn negative_bloom_lookup(
+        &self,
+        negative_bloom: &NegativeBloom,
+        operation: Option<Operation>,
+        input: &OperationInput,
+        cfg: &ConfigBag,
+    ) -> Option<NegativeBloomAttempt> {
+        let evict = cfg
+            .load::<RequestSettings>()
+            .is_some_and(|settings| settings.evict);
+        if operation != Some(Operation::GetItem) || evict {
+            if evict || !operation.is_some_and(|operation| operation.is_item_read()) {
+                negative_bloom.clear();
+            }
+            return None;
+        }
+        if input.consistent_read() {
+            return None;
+        }
+        let key = self.local_key(input, cfg)?;
+        let ttl = *self.ttl.lock().unwrap_or_else(PoisonError::into_inner);
+        negative_bloom.expire(self.config.clock.now(), ttl);
+        let known_absent = negative_bloom.contains(&key);
+        if known_absent && self.config.rng.next_f64() >= NEGATIVE_BLOOM_VERIFY_RATE {
+            return Some(NegativeBloomAttempt::Absent);
+        }
+        Some(NegativeBloomAttempt::Check { key, known_absent })
+    }
+}
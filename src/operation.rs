@@ -0,0 +1,169 @@
+macro_rules! operations {
+    ($($operation:ident),+ $(,)?) => {
+        /// A DynamoDB API operation, as named in the request's `x-amz-target` header.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[non_exhaustive]
+        pub enum Operation {
+            $(
+                #[doc = concat!("The `", stringify!($operation), "` operation")]
+                $operation,
+            )+
+        }
+
+        impl Operation {
+            /// Every operation, in alphabetical order.
+            pub const ALL: &'static [Operation] = &[$(Operation::$operation,)+];
+
+            /// The operation's name in the DynamoDB API, like `"GetItem"`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(Operation::$operation => stringify!($operation),)+
+                }
+            }
+
+            /// Look up an operation by its name in the DynamoDB API.
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($operation) => Some(Operation::$operation),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+operations!(
+    BatchExecuteStatement,
+    BatchGetItem,
+    BatchWriteItem,
+    CreateBackup,
+    CreateGlobalTable,
+    CreateTable,
+    DeleteBackup,
+    DeleteItem,
+    DeleteResourcePolicy,
+    DeleteTable,
+    DescribeBackup,
+    DescribeContinuousBackups,
+    DescribeContributorInsights,
+    DescribeEndpoints,
+    DescribeExport,
+    DescribeGlobalTable,
+    DescribeGlobalTableSettings,
+    DescribeImport,
+    DescribeKinesisStreamingDestination,
+    DescribeLimits,
+    DescribeTable,
+    DescribeTableReplicaAutoScaling,
+    DescribeTimeToLive,
+    DisableKinesisStreamingDestination,
+    EnableKinesisStreamingDestination,
+    ExecuteStatement,
+    ExecuteTransaction,
+    ExportTableToPointInTime,
+    GetItem,
+    GetResourcePolicy,
+    ImportTable,
+    ListBackups,
+    ListContributorInsights,
+    ListExports,
+    ListGlobalTables,
+    ListImports,
+    ListTables,
+    ListTagsOfResource,
+    PutItem,
+    PutResourcePolicy,
+    Query,
+    RestoreTableFromBackup,
+    RestoreTableToPointInTime,
+    Scan,
+    TagResource,
+    TransactGetItems,
+    TransactWriteItems,
+    UntagResource,
+    UpdateContinuousBackups,
+    UpdateContributorInsights,
+    UpdateGlobalTable,
+    UpdateGlobalTableSettings,
+    UpdateItem,
+    UpdateKinesisStreamingDestination,
+    UpdateTable,
+    UpdateTableReplicaAutoScaling,
+    UpdateTimeToLive,
+);
+
+impl Operation {
+    /// The HTTP method requests for this operation use.
+    pub(crate) fn method(&self) -> http::Method {
+        // The DynamoDB JSON protocol sends every operation as a POST
+        http::Method::POST
+    }
+
+    /// Whether this operation only reads items, so repeating it is always safe.
+    pub(crate) fn is_item_read(&self) -> bool {
+        matches!(
+            self,
+            Operation::GetItem
+                | Operation::BatchGetItem
+                | Operation::Query
+                | Operation::Scan
+                | Operation::TransactGetItems
+        )
+    }
+
+    /// Whether this operation only writes items.
+    ///
+    /// PartiQL statements are neither, since they can do either.
+    pub(crate) fn is_item_write(&self) -> bool {
+        matches!(
+            self,
+            Operation::PutItem
+                | Operation::UpdateItem
+                | Operation::DeleteItem
+                | Operation::BatchWriteItem
+                | Operation::TransactWriteItems
+        )
+    }
+
+    /// Whether this operation manages tables, backups or other resources rather than items.
+    ///
+    /// These are never worth caching, so they skip the proxy unless
+    /// [proxy_control_plane](crate::AcceleratorConfig::proxy_control_plane) is set.
+    pub(crate) fn is_control_plane(&self) -> bool {
+        !matches!(
+            self,
+            Operation::GetItem
+                | Operation::BatchGetItem
+                | Operation::Query
+                | Operation::Scan
+                | Operation::TransactGetItems
+                | Operation::PutItem
+                | Operation::UpdateItem
+                | Operation::DeleteItem
+                | Operation::BatchWriteItem
+                | Operation::TransactWriteItems
+                | Operation::ExecuteStatement
+                | Operation::BatchExecuteStatement
+                | Operation::ExecuteTransaction
+        )
+    }
+
+    /// Identify the operation from an `x-amz-target` header value, like `"DynamoDB_20120810.GetItem"`.
+    pub fn from_target(target: &str) -> Option<Self> {
+        let (_api_version, name) = target.split_once('.')?;
+        Self::from_name(name)
+    }
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Operation {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
@@ -0,0 +1,296 @@
+use aws_sdk_dynamodb::config::http::HttpRequest;
+use aws_smithy_types::date_time::Format;
+use aws_smithy_types::{DateTime, Document, Number};
+
+use crate::{ItemKey, Operation, json, projection};
+
+/// What the accelerator knows about a DynamoDB request when it decides how to handle it.
+///
+/// This is read from the serialized request, so it looks the same for every operation. It is
+/// passed to callbacks like [with_dynamic_ttl](crate::AcceleratorRequestExt::with_dynamic_ttl).
+#[derive(Debug)]
+pub struct OperationInput {
+    operation: Option<Operation>,
+    body: Option<Document>,
+}
+
+impl OperationInput {
+    /// Inspect a serialized DynamoDB request for `operation`.
+    pub(crate) fn from_request(request: &HttpRequest, operation: Option<Operation>) -> Self {
+        Self {
+            operation,
+            body: request.body().bytes().and_then(json::parse),
+        }
+    }
+
+    /// The DynamoDB operation, if it could be identified.
+    pub fn operation(&self) -> Option<Operation> {
+        self.operation
+    }
+
+    /// The names (or ARNs) of the tables the request refers to.
+    ///
+    /// Most operations name one table. Batch and transaction operations can name several.
+    pub fn table_names(&self) -> Vec<&str> {
+        let Some(body) = &self.body else {
+            return Vec::new();
+        };
+        let mut tables = Vec::new();
+        if let Some(Document::String(table)) = json::field(body, "TableName") {
+            tables.push(table.as_str());
+        }
+        if let Some(Document::Object(request_items)) = json::field(body, "RequestItems") {
+            tables.extend(request_items.keys().map(String::as_str));
+        }
+        if let Some(Document::Array(transact_items)) = json::field(body, "TransactItems") {
+            for item in transact_items {
+                let Document::Object(actions) = item else {
+                    continue;
+                };
+                // Each item holds exactly one action, like {"Get": {"TableName": ...}}
+                for action in actions.values() {
+                    if let Some(Document::String(table)) = json::field(action, "TableName") {
+                        tables.push(table.as_str());
+                    }
+                }
+            }
+        }
+        tables.sort_unstable();
+        tables.dedup();
+        tables
+    }
+
+    /// The primary key of the item the request refers to, for single-item operations like GetItem.
+    pub fn key(&self) -> Option<ItemKey> {
+        json::item(self.field("Key")?)
+    }
+
+    /// The values the request's expressions refer to, like `:status` in a Query's
+    /// `FilterExpression`, keyed by their placeholders.
+    pub fn expression_attribute_values(&self) -> Option<ItemKey> {
+        json::item(self.field("ExpressionAttributeValues")?)
+    }
+
+    /// The PartiQL statements of a BatchExecuteStatement, in order.
+    pub(crate) fn statements(&self) -> Vec<&str> {
+        let Some(Document::Array(statements)) = self.field("Statements") else {
+            return Vec::new();
+        };
+        statements
+            .iter()
+            .filter_map(|statement| match json::field(statement, "Statement") {
+                Some(Document::String(statement)) => Some(statement.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the request asks for strongly consistent reads.
+    ///
+    /// This is `ConsistentRead` for GetItem, Query and Scan, and `ConsistentRead` of any table in
+    /// a BatchGetItem. TransactGetItems is always strongly consistent.
+    pub fn consistent_read(&self) -> bool {
+        if self.operation == Some(Operation::TransactGetItems) {
+            return true;
+        }
+        let consistent = |request: &Document| {
+            json::field(request, "ConsistentRead") == Some(&Document::Bool(true))
+        };
+        let Some(body) = &self.body else {
+            return false;
+        };
+        consistent(body)
+            || matches!(
+                json::field(body, "RequestItems"),
+                Some(Document::Object(request_items))
+                    if request_items.values().any(consistent)
+            )
+    }
+
+    /// Whether the request asks DynamoDB to report the capacity it consumed.
+    ///
+    /// This is `ReturnConsumedCapacity` set to anything but `NONE`.
+    pub fn returns_consumed_capacity(&self) -> bool {
+        match self.field("ReturnConsumedCapacity") {
+            Some(Document::String(value)) => value != "NONE",
+            _ => false,
+        }
+    }
+
+    /// A stable hash of the operation and everything in the request that shapes its response.
+    ///
+    /// Identical requests hash the same in every process and on every platform, so the proxy can
+    /// use it to recognize duplicates. Object fields are hashed in sorted order since JSON does
+    /// not order them.
+    pub(crate) fn request_hash(&self) -> Option<String> {
+        hash_request(self.operation?, self.body.as_ref()?)
+    }
+
+    /// The operation and its request body in [canonical](json::canonical) form, which only
+    /// identical requests share.
+    pub(crate) fn canonical_request(&self) -> Option<String> {
+        Some(format!(
+            "{}\n{}",
+            self.operation?.name(),
+            json::canonical(self.body.as_ref()?)
+        ))
+    }
+
+    /// The [request_hash](Self::request_hash) of a Query, with the timestamps its key condition
+    /// compares against rewritten in one form.
+    ///
+    /// A string value that is an RFC 3339 timestamp, like `2027-01-15T10:00:00.000+02:00`, is
+    /// hashed as the same instant in UTC with no trailing fractional zeros,
+    /// `2027-01-15T08:00:00Z`, so equivalent time ranges hash the same however they're written.
+    pub(crate) fn canonical_query_hash(&self) -> Option<String> {
+        let mut body = self.body.clone()?;
+        let Document::Object(fields) = &mut body else {
+            return None;
+        };
+        let placeholders: Vec<String> = match fields.get("KeyConditionExpression") {
+            Some(Document::String(condition)) => value_placeholders(condition),
+            _ => return None,
+        };
+        if let Some(Document::Object(values)) = fields.get_mut("ExpressionAttributeValues") {
+            for placeholder in placeholders {
+                if let Some(Document::Object(value)) = values.get_mut(&placeholder)
+                    && let Some(Document::String(string)) = value.get_mut("S")
+                    && let Some(timestamp) = canonical_timestamp(string)
+                {
+                    *string = timestamp;
+                }
+            }
+        }
+        hash_request(self.operation?, &body)
+    }
+
+    /// A stable hash of the table and primary key of the single item the request refers to.
+    ///
+    /// The key is `Key`, or for a PutItem the attributes of `Item` named by `key_attributes`.
+    pub(crate) fn key_hash(&self, key_attributes: Option<(&str, Option<&str>)>) -> Option<u64> {
+        let Some(Document::String(table)) = self.field("TableName") else {
+            return None;
+        };
+        let put_key;
+        let key = match (self.field("Key"), self.field("Item"), key_attributes) {
+            (Some(key), _, _) => key,
+            (None, Some(Document::Object(item)), Some((partition_key, sort_key))) => {
+                put_key = Document::Object(
+                    std::iter::once(partition_key)
+                        .chain(sort_key)
+                        .map(|name| Some((name.to_string(), item.get(name)?.clone())))
+                        .collect::<Option<_>>()?,
+                );
+                &put_key
+            }
+            _ => return None,
+        };
+        let mut hasher = Fnv1a::default();
+        hasher.write_prefixed(table.as_bytes());
+        hash_document(&mut hasher, key);
+        Some(hasher.0)
+    }
+
+    /// A top level field of the request body.
+    pub(crate) fn field(&self, name: &str) -> Option<&Document> {
+        json::field(self.body.as_ref()?, name)
+    }
+
+    /// The attributes the request projects, see [projection::requested_attributes].
+    pub(crate) fn requested_attributes(&self) -> Option<Vec<String>> {
+        projection::requested_attributes(self.body.as_ref()?)
+    }
+}
+
+fn hash_request(operation: Operation, body: &Document) -> Option<String> {
+    let mut hasher = Fnv1a::default();
+    hasher.write(operation.name().as_bytes());
+    hash_document(&mut hasher, body);
+    Some(format!("{:016x}", hasher.0))
+}
+
+/// The `:value` placeholders an expression refers to.
+fn value_placeholders(expression: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+    let mut rest = expression;
+    while let Some(start) = rest.find(':') {
+        let name_len = rest[start + 1..]
+            .find(|char: char| !char.is_ascii_alphanumeric() && char != '_')
+            .unwrap_or(rest.len() - start - 1);
+        placeholders.push(rest[start..start + 1 + name_len].to_string());
+        rest = &rest[start + 1 + name_len..];
+    }
+    placeholders
+}
+
+/// `value` in UTC if it is an RFC 3339 timestamp.
+fn canonical_timestamp(value: &str) -> Option<String> {
+    DateTime::from_str(value, Format::DateTimeWithOffset)
+        .ok()?
+        .fmt(Format::DateTime)
+        .ok()
+}
+
+/// 64-bit FNV-1a, which unlike the standard library's hasher is specified and stable.
+pub(crate) struct Fnv1a(pub(crate) u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    pub(crate) fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    /// Write a length-prefixed value, so adjacent values can't run together.
+    fn write_prefixed(&mut self, bytes: &[u8]) {
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+}
+
+fn hash_document(hasher: &mut Fnv1a, document: &Document) {
+    match document {
+        Document::Object(fields) => {
+            hasher.write(b"o");
+            hasher.write(&(fields.len() as u64).to_le_bytes());
+            let mut fields: Vec<_> = fields.iter().collect();
+            fields.sort_unstable_by_key(|(name, _)| *name);
+            for (name, value) in fields {
+                hasher.write_prefixed(name.as_bytes());
+                hash_document(hasher, value);
+            }
+        }
+        Document::Array(values) => {
+            hasher.write(b"a");
+            hasher.write(&(values.len() as u64).to_le_bytes());
+            for value in values {
+                hash_document(hasher, value);
+            }
+        }
+        Document::Number(Number::PosInt(number)) => {
+            hasher.write(b"u");
+            hasher.write(&number.to_le_bytes());
+        }
+        Document::Number(Number::NegInt(number)) => {
+            hasher.write(b"i");
+            hasher.write(&number.to_le_bytes());
+        }
+        Document::Number(Number::Float(number)) => {
+            hasher.write(b"f");
+            hasher.write(&number.to_le_bytes());
+        }
+        Document::String(string) => {
+            hasher.write(b"s");
+            hasher.write_prefixed(string.as_bytes());
+        }
+        Document::Bool(bool) => hasher.write(if *bool { b"t" } else { b"F" }),
+        Document::Null => hasher.write(b"n"),
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use opentelemetry::KeyValue;
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+
+use crate::{AcceleratorMetrics, Operation, SkipReason};
+
+/// [AcceleratorMetrics] that records to OpenTelemetry instruments.
+///
+/// Requires the `otel` feature. The instruments are:
+///
+/// | Name | Instrument | Attributes |
+/// |------|------------|------------|
+/// | `momento.accelerator.hits` | counter | `operation` |
+/// | `momento.accelerator.misses` | counter | `operation` |
+/// | `momento.accelerator.proxy_latency` | histogram, in seconds | `operation` |
+/// | `momento.accelerator.bypasses` | counter | `operation`, `reason` |
+/// | `momento.accelerator.proxy_unavailable` | counter | `operation` |
+///
+/// `operation` is omitted when it isn't known, and `reason` is the `reason` tag a
+/// [SkipReason] serializes with, like `denied_table`.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use opentelemetry_sdk::metrics::data::{AggregatedMetrics, MetricData};
+/// # use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+/// # use std::sync::atomic::{AtomicUsize, Ordering};
+/// use momento_ddb_interceptor::{MomentoAccelerator, OtelMetrics, accelerator_config};
+/// use opentelemetry::metrics::MeterProvider;
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let exporter = InMemoryMetricExporter::default();
+/// # let meter_provider = SdkMeterProvider::builder()
+/// #     .with_reader(PeriodicReader::builder(exporter.clone()).build())
+/// #     .build();
+/// # // The proxy misses the first read and then hits
+/// # let reads = AtomicUsize::new(0);
+/// # let http_client = infallible_client_fn(move |_request| {
+/// #     let status = if reads.fetch_add(1, Ordering::Relaxed) == 0 { "miss" } else { "hit" };
+/// #     http::Response::builder()
+/// #         .header("x-cache-status", status)
+/// #         .body("{}")
+/// #         .unwrap()
+/// # });
+/// let meter = meter_provider.meter("my-service");
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60))
+///             .metrics(OtelMetrics::new(&meter)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+/// for _ in 0..3 {
+///     let _ = client.get_item().table_name("my-table").send().await;
+/// }
+///
+/// # meter_provider.force_flush().unwrap();
+/// # let metrics = exporter.get_finished_metrics().unwrap();
+/// # let sum = |name: &str| -> u64 {
+/// #     let metric = metrics
+/// #         .iter()
+/// #         .flat_map(|resource| resource.scope_metrics())
+/// #         .flat_map(|scope| scope.metrics())
+/// #         .find(|metric| metric.name() == name)
+/// #         .unwrap();
+/// #     match metric.data() {
+/// #         AggregatedMetrics::U64(MetricData::Sum(sum)) => {
+/// #             sum.data_points().map(|point| point.value()).sum()
+/// #         }
+/// #         AggregatedMetrics::F64(MetricData::Histogram(histogram)) => {
+/// #             histogram.data_points().map(|point| point.count()).sum()
+/// #         }
+/// #         data => panic!("unexpected {data:?}"),
+/// #     }
+/// # };
+/// // Recorded to the meter provider's exporter
+/// assert_eq!(sum("momento.accelerator.hits"), 2);
+/// assert_eq!(sum("momento.accelerator.misses"), 1);
+/// assert_eq!(sum("momento.accelerator.proxy_latency"), 3);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct OtelMetrics {
+    hits: Counter<u64>,
+    misses: Counter<u64>,
+    proxy_latency: Histogram<f64>,
+    bypasses: Counter<u64>,
+    proxy_unavailable: Counter<u64>,
+}
+
+impl OtelMetrics {
+    /// Create the instruments on `meter`.
+    pub fn new(meter: &Meter) -> Self {
+        Self {
+            hits: meter
+                .u64_counter("momento.accelerator.hits")
+                .with_description("Responses served from the cache")
+                .build(),
+            misses: meter
+                .u64_counter("momento.accelerator.misses")
+                .with_description("Responses the proxy had to read from DynamoDB")
+                .build(),
+            proxy_latency: meter
+                .f64_histogram("momento.accelerator.proxy_latency")
+                .with_description("Time from sending a request to the proxy until it responded")
+                .with_unit("s")
+                .build(),
+            bypasses: meter
+                .u64_counter("momento.accelerator.bypasses")
+                .with_description("Requests sent directly to DynamoDB instead of the proxy")
+                .build(),
+            proxy_unavailable: meter
+                .u64_counter("momento.accelerator.proxy_unavailable")
+                .with_description("Attempts the proxy failed to answer")
+                .build(),
+        }
+    }
+}
+
+impl AcceleratorMetrics for OtelMetrics {
+    fn cache_hit(&self, operation: Option<Operation>) {
+        self.hits.add(1, &attributes(operation));
+    }
+
+    fn cache_miss(&self, operation: Option<Operation>) {
+        self.misses.add(1, &attributes(operation));
+    }
+
+    fn proxy_latency(&self, operation: Option<Operation>, latency: Duration) {
+        self.proxy_latency
+            .record(latency.as_secs_f64(), &attributes(operation));
+    }
+
+    fn bypass(&self, operation: Option<Operation>, reason: &SkipReason) {
+        let mut attributes = attributes(operation);
+        attributes.push(KeyValue::new("reason", reason_tag(reason)));
+        self.bypasses.add(1, &attributes);
+    }
+
+    fn proxy_unavailable(&self, operation: Option<Operation>) {
+        self.proxy_unavailable.add(1, &attributes(operation));
+    }
+}
+
+fn attributes(operation: Option<Operation>) -> Vec<KeyValue> {
+    operation
+        .map(|operation| KeyValue::new("operation", operation.name()))
+        .into_iter()
+        .collect()
+}
+
+/// The `reason` tag `reason` serializes with.
+fn reason_tag(reason: &SkipReason) -> &'static str {
+    match reason {
+        SkipReason::Disabled => "disabled",
+        SkipReason::ShutDown => "shut_down",
+        SkipReason::InvalidConfig { .. } => "invalid_config",
+        SkipReason::Warmup => "warmup",
+        SkipReason::CircuitOpen => "circuit_open",
+        SkipReason::ProxyOverloaded => "proxy_overloaded",
+        SkipReason::InflightLimit => "inflight_limit",
+        SkipReason::MethodNotProxied { .. } => "method_not_proxied",
+        SkipReason::RegionNotEnabled { .. } => "region_not_enabled",
+        SkipReason::DeniedTable { .. } => "denied_table",
+        SkipReason::DisabledRoute { .. } => "disabled_route",
+        SkipReason::NotRouted => "not_routed",
+        SkipReason::LowRequestRate { .. } => "low_request_rate",
+        SkipReason::CostBudgetExhausted => "cost_budget_exhausted",
+        SkipReason::HitTimeout => "hit_timeout",
+        SkipReason::CapacityRequested => "capacity_requested",
+        SkipReason::ControlPlane { .. } => "control_plane",
+        SkipReason::BatchHasWrites => "batch_has_writes",
+        SkipReason::AlreadyProxied { .. } => "already_proxied",
+        SkipReason::ZeroTtl => "zero_ttl",
+        SkipReason::RuntimeError { .. } => "runtime_error",
+    }
+}
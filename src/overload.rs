@@ -0,0 +1,60 @@
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_smithy_types::DateTime;
+use aws_smithy_types::date_time::Format;
+
+/// Routes around the proxy while it has asked clients to back off.
+#[derive(Debug, Default)]
+pub(crate) struct Overload {
+    shed_until: Mutex<Option<SystemTime>>,
+}
+
+impl Overload {
+    /// Whether requests may be sent to the proxy at `now`.
+    pub(crate) fn allows(&self, now: SystemTime) -> bool {
+        match *self.shed_until() {
+            Some(shed_until) => shed_until <= now,
+            None => true,
+        }
+    }
+
+    /// Start shedding if `response` is an overload signal from the proxy.
+    pub(crate) fn record(&self, response: &HttpResponse, now: SystemTime, log_target: &str) {
+        let status = response.status().as_u16();
+        if status != 429 && status != 503 {
+            return;
+        }
+        let Some(shed_until) = response
+            .headers()
+            .get("retry-after")
+            .and_then(|retry_after| retry_after_deadline(retry_after, now))
+        else {
+            return;
+        };
+        log::warn!(target: log_target, "momento proxy is overloaded, bypassing it until {shed_until:?}");
+        let mut current = self.shed_until();
+        // A later signal never shortens the window
+        if current.is_none_or(|current| current < shed_until) {
+            *current = Some(shed_until);
+        }
+    }
+
+    fn shed_until(&self) -> std::sync::MutexGuard<'_, Option<SystemTime>> {
+        self.shed_until
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// When a `Retry-After` value, either delay seconds or an HTTP date, allows retrying.
+fn retry_after_deadline(retry_after: &str, now: SystemTime) -> Option<SystemTime> {
+    let retry_after = retry_after.trim();
+    match retry_after.parse::<u64>() {
+        Ok(seconds) => now.checked_add(Duration::from_secs(seconds)),
+        Err(_) => DateTime::from_str(retry_after, Format::HttpDate)
+            .ok()
+            .and_then(|date| SystemTime::try_from(date).ok()),
+    }
+}
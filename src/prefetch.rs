@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_sdk_dynamodb::error::SdkError;
+use aws_sdk_dynamodb::operation::get_item::GetItemError;
+use aws_sdk_dynamodb::types::AttributeValue;
+
+use crate::LOG_TARGET;
+
+/// A DynamoDB item key: the key attribute names and their values.
+pub type ItemKey = HashMap<String, AttributeValue>;
+
+/// Warm the cache by fetching `keys` from `table_name` through the accelerator.
+///
+/// `client` must be configured with [with_momento_accelerator](crate::MomentoAccelerator::with_momento_accelerator),
+/// so each GetItem goes through the proxy and leaves the item in the cache. The items themselves
+/// are discarded. The result for each key is returned in the same order as `keys`.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use aws_sdk_dynamodb::types::AttributeValue;
+/// use momento_ddb_interceptor::{MomentoAccelerator, accelerator_config, prefetch};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let proxied_bodies = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let proxied_bodies = proxied_bodies.clone();
+/// #     move |request| {
+/// #         assert_eq!(request.uri().host(), Some("api.cache.cell-us-west-2-1.prod.a.momentohq.com"));
+/// #         let body = String::from_utf8(request.body().bytes().unwrap().to_vec()).unwrap();
+/// #         proxied_bodies.lock().unwrap().push(body);
+/// #         http::Response::builder().body("{}").unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let keys = ["a", "b", "c"].map(|pk| [("pk".to_string(), AttributeValue::S(pk.into()))].into());
+/// let results = prefetch(&client, "my-table", keys).await;
+/// assert!(results.iter().all(Result::is_ok));
+/// # let proxied_bodies = proxied_bodies.lock().unwrap();
+/// # assert_eq!(proxied_bodies.len(), 3);
+/// # for (body, pk) in proxied_bodies.iter().zip(["a", "b", "c"]) {
+/// #     assert!(body.contains(&format!(r#"{{"S":"{pk}"}}"#)));
+/// # }
+/// # }
+/// ```
+pub async fn prefetch(
+    client: &aws_sdk_dynamodb::Client,
+    table_name: impl Into<String>,
+    keys: impl IntoIterator<Item = ItemKey>,
+) -> Vec<Result<(), SdkError<GetItemError, HttpResponse>>> {
+    let table_name = table_name.into();
+    let mut results = Vec::new();
+    for key in keys {
+        let result = client
+            .get_item()
+            .table_name(&table_name)
+            .set_key(Some(key))
+            .send()
+            .await;
+        if let Err(e) = &result {
+            log::debug!(target: LOG_TARGET, "failed to prefetch an item from {table_name}: {e}");
+        }
+        results.push(result.map(|_item| ()));
+    }
+    results
+}
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use aws_smithy_types::Document;
+
+use crate::Operation;
+
+/// The projection added to reads that don't choose their attributes, set by
+/// [default_projection](crate::AcceleratorConfig::default_projection).
+#[derive(Debug, Clone)]
+pub(crate) struct DefaultProjection {
+    pub(crate) expression: String,
+    pub(crate) names: HashMap<String, String>,
+}
+
+impl DefaultProjection {
+    /// Add the projection to the body of an `operation` request, returning whether it changed.
+    pub(crate) fn apply(&self, operation: Option<Operation>, body: &mut Document) -> bool {
+        let Document::Object(fields) = body else {
+            return false;
+        };
+        match operation {
+            Some(Operation::GetItem | Operation::Query | Operation::Scan) => self.add_to(fields),
+            Some(Operation::BatchGetItem) => {
+                let Some(Document::Object(tables)) = fields.get_mut("RequestItems") else {
+                    return false;
+                };
+                let mut changed = false;
+                for keys_and_attributes in tables.values_mut() {
+                    if let Document::Object(fields) = keys_and_attributes {
+                        changed |= self.add_to(fields);
+                    }
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn add_to(&self, fields: &mut HashMap<String, Document>) -> bool {
+        // Any of these already choose which attributes come back
+        if ["ProjectionExpression", "AttributesToGet", "Select"]
+            .iter()
+            .any(|field| fields.contains_key(*field))
+        {
+            return false;
+        }
+        // A placeholder the request already uses for another name can't be reused
+        let conflicts = match fields.get("ExpressionAttributeNames") {
+            Some(Document::Object(names)) => self.names.iter().any(|(placeholder, name)| {
+                names
+                    .get(placeholder)
+                    .is_some_and(|existing| *existing != Document::String(name.clone()))
+            }),
+            Some(_) => true,
+            None => false,
+        };
+        if conflicts {
+            return false;
+        }
+        if !self.names.is_empty()
+            && let Document::Object(names) = fields
+                .entry("ExpressionAttributeNames".to_string())
+                .or_insert_with(|| Document::Object(HashMap::new()))
+        {
+            for (placeholder, name) in &self.names {
+                names.insert(placeholder.clone(), Document::String(name.clone()));
+            }
+        }
+        fields.insert(
+            "ProjectionExpression".to_string(),
+            Document::String(self.expression.clone()),
+        );
+        true
+    }
+}
+
+/// The attribute paths a request's `ProjectionExpression` or `AttributesToGet` asks for, with
+/// `#name` placeholders resolved from its `ExpressionAttributeNames`, sorted and deduplicated.
+///
+/// This is `None` for a request that reads every attribute, or whose projection refers to a
+/// placeholder it doesn't define.
+pub(crate) fn requested_attributes(body: &Document) -> Option<Vec<String>> {
+    let Document::Object(fields) = body else {
+        return None;
+    };
+    let mut attributes =
+        if let Some(Document::String(expression)) = fields.get("ProjectionExpression") {
+            let names = match fields.get("ExpressionAttributeNames") {
+                Some(Document::Object(names)) => Some(names),
+                _ => None,
+            };
+            expression
+                .split(',')
+                .map(|path| resolve_path(path.trim(), names))
+                .collect::<Option<Vec<_>>>()?
+        } else if let Some(Document::Array(attributes)) = fields.get("AttributesToGet") {
+            attributes
+                .iter()
+                .map(|attribute| match attribute {
+                    Document::String(attribute) => Some(attribute.clone()),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?
+        } else {
+            return None;
+        };
+    attributes.sort_unstable();
+    attributes.dedup();
+    Some(attributes)
+}
+
+/// `path`, like `#a.b[2]`, with each placeholder replaced by the name it stands for.
+fn resolve_path(path: &str, names: Option<&HashMap<String, Document>>) -> Option<String> {
+    let segments = path.split('.').map(|segment| {
+        // An element of a list attribute, like `tags[0]`, keeps its index
+        let (name, index) = segment.split_at(segment.find('[').unwrap_or(segment.len()));
+        if !name.starts_with('#') {
+            return Some(segment.to_string());
+        }
+        match names?.get(name)? {
+            Document::String(name) => Some(format!("{name}{index}")),
+            _ => None,
+        }
+    });
+    Some(segments.collect::<Option<Vec<_>>>()?.join("."))
+}
@@ -0,0 +1,251 @@
+use std::fmt::Display;
+
+#[cfg(feature = "serde")]
+use aws_sdk_dynamodb::config::http::HttpResponse;
+
+/// An error reported by the Momento proxy itself, rather than by DynamoDB.
+///
+/// With the `serde` feature, the proxy's error responses are parsed into this type. See
+/// [ProxyError::from_response] and [AcceleratorResponse::proxy_error](crate::AcceleratorResponse::proxy_error).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProxyError {
+    /// The proxy rejected the Momento auth token. Code `AuthenticationError`.
+    Authentication {
+        /// The proxy's description of the error
+        message: String,
+    },
+    /// The configured cache does not exist. Code `CacheNotFound`.
+    CacheNotFound {
+        /// The proxy's description of the error
+        message: String,
+    },
+    /// The request exceeded a Momento limit, like a request rate or item size. Code `LimitExceeded`.
+    LimitExceeded {
+        /// The proxy's description of the error
+        message: String,
+    },
+    /// The proxy could not interpret the request. Code `InvalidArgument`.
+    InvalidArgument {
+        /// The proxy's description of the error
+        message: String,
+    },
+    /// The proxied attempt timed out, so the proxy or DynamoDB behind it was too slow.
+    ///
+    /// Unlike the other variants this is not sent by the proxy: the accelerator puts it in place
+    /// of a timeout error from the proxied attempt, so that it can be told apart from a timeout
+    /// talking to DynamoDB directly. Reach it through the error's sources.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_sdk_dynamodb::config::timeout::TimeoutConfig;
+    /// # use aws_smithy_http_client::test_util::NeverClient;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, ProxyError,
+    ///     accelerator_config,
+    /// };
+    /// use std::error::Error;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   // The proxy never responds
+    /// #   .http_client(NeverClient::new())
+    /// #   .retry_config(RetryConfig::disabled())
+    ///     .timeout_config(
+    ///         TimeoutConfig::builder()
+    ///             .operation_attempt_timeout(Duration::from_millis(10))
+    ///             .build(),
+    ///     )
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// let error = client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await
+    ///     .expect_err("the proxy timed out");
+    ///
+    /// let mut source = error.source();
+    /// let proxy_error = std::iter::from_fn(|| {
+    ///     let error = source?;
+    ///     source = error.source();
+    ///     Some(error)
+    /// })
+    /// .find_map(|error| error.downcast_ref::<ProxyError>());
+    /// assert!(matches!(proxy_error, Some(ProxyError::Timeout { .. })));
+    /// assert!(matches!(response.proxy_error(), Some(ProxyError::Timeout { .. })));
+    /// # }
+    /// ```
+    Timeout {
+        /// The timeout error that was replaced
+        message: String,
+    },
+    /// The response to a proxied attempt did not carry the proxy's `x-momento-proxy: 1` marker,
+    /// so it may not have come from the proxy. See
+    /// [verify_proxy_response](crate::AcceleratorConfig::verify_proxy_response).
+    ///
+    /// Like [Timeout](Self::Timeout), this is not sent by the proxy.
+    Unverified {
+        /// Which response was missing the marker
+        message: String,
+    },
+    /// The proxy redirected the request, which the SDK can't follow for a signed request. See
+    /// [follow_proxy_redirects](crate::AcceleratorConfig::follow_proxy_redirects).
+    ///
+    /// Like [Timeout](Self::Timeout), this is not sent by the proxy.
+    Redirected {
+        /// The `Location` the proxy redirected to, empty if it had none
+        location: String,
+    },
+    /// An error code this version of the crate does not know about.
+    Other {
+        /// The error code the proxy sent
+        code: String,
+        /// The proxy's description of the error
+        message: String,
+    },
+}
+
+impl ProxyError {
+    /// Classify an error from its code and message.
+    pub fn from_code(code: impl Into<String>, message: impl Into<String>) -> Self {
+        let code = code.into();
+        let message = message.into();
+        match code.as_str() {
+            "AuthenticationError" => ProxyError::Authentication { message },
+            "CacheNotFound" => ProxyError::CacheNotFound { message },
+            "LimitExceeded" => ProxyError::LimitExceeded { message },
+            "InvalidArgument" => ProxyError::InvalidArgument { message },
+            _ => ProxyError::Other { code, message },
+        }
+    }
+
+    /// Parse the proxy's error body, `{ "error": "<message>", "code": "<code>" }`, from a response.
+    ///
+    /// Returns `None` for successful responses and for error responses that did not come from
+    /// the proxy, like errors from DynamoDB.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, ProxyError,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let http_client = infallible_client_fn(|_request| {
+    /// #     http::Response::builder()
+    /// #         .status(404)
+    /// #         .body(r#"{"error": "cache my-dynamo-cache does not exist", "code": "CacheNotFound"}"#)
+    /// #         .unwrap()
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// let error = client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await
+    ///     .expect_err("the cache is missing");
+    ///
+    /// let expected = ProxyError::CacheNotFound {
+    ///     message: "cache my-dynamo-cache does not exist".to_string(),
+    /// };
+    /// assert_eq!(response.proxy_error(), Some(expected.clone()));
+    /// assert_eq!(ProxyError::from_response(error.raw_response().unwrap()), Some(expected));
+    /// # }
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn from_response(response: &HttpResponse) -> Option<Self> {
+        #[derive(serde::Deserialize)]
+        struct ProxyErrorBody {
+            error: String,
+            code: String,
+        }
+
+        if response.status().is_success() {
+            return None;
+        }
+        let body: ProxyErrorBody = serde_json::from_slice(response.body().bytes()?).ok()?;
+        Some(Self::from_code(body.code, body.error))
+    }
+
+    /// The proxy's description of the error.
+    pub fn message(&self) -> &str {
+        match self {
+            ProxyError::Authentication { message }
+            | ProxyError::CacheNotFound { message }
+            | ProxyError::LimitExceeded { message }
+            | ProxyError::InvalidArgument { message }
+            | ProxyError::Timeout { message }
+            | ProxyError::Unverified { message }
+            | ProxyError::Other { message, .. } => message,
+            ProxyError::Redirected { location } => location,
+        }
+    }
+}
+
+impl Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyError::Authentication { message } => {
+                write!(f, "momento proxy authentication failed: {message}")
+            }
+            ProxyError::CacheNotFound { message } => {
+                write!(f, "momento cache not found: {message}")
+            }
+            ProxyError::LimitExceeded { message } => {
+                write!(f, "momento limit exceeded: {message}")
+            }
+            ProxyError::InvalidArgument { message } => {
+                write!(f, "momento proxy rejected the request: {message}")
+            }
+            ProxyError::Timeout { message } => write!(f, "momento proxy timed out: {message}"),
+            ProxyError::Unverified { message } => {
+                write!(f, "response did not come from the momento proxy: {message}")
+            }
+            ProxyError::Redirected { location } => {
+                write!(f, "momento proxy redirected the request to {location}")
+            }
+            ProxyError::Other { code, message } => {
+                write!(f, "momento proxy error {code}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ProxyError {}
@@ -0,0 +1,123 @@
+use std::sync::{Arc, Mutex, PoisonError};
+
+use crate::{Operation, RewriteDecision};
+
+/// Records every [RewriteDecision] a client makes, for comparing against a snapshot in tests.
+///
+/// Attach one with [record_decisions](crate::AcceleratorConfig::record_decisions). Clones share
+/// the same log. Available with the `test-util` feature.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// use momento_ddb_interceptor::{DecisionRecorder, MomentoAccelerator, accelerator_config};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let http_client = infallible_client_fn(|_| http::Response::builder().body("{}").unwrap());
+/// let recorder = DecisionRecorder::new();
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60))
+///             .table_cache("orders", "orders-cache")
+///             .deny_tables(["ledger".to_string()])
+///             .record_decisions(&recorder),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let _ = client.get_item().table_name("users").send().await;
+/// let _ = client.get_item().table_name("orders").send().await;
+/// let _ = client.put_item().table_name("ledger").send().await;
+/// let _ = client.list_tables().send().await;
+///
+/// recorder.assert_matches(
+///     r#"[
+///         {
+///             "operation": "GetItem",
+///             "decision": "proxy",
+///             "uri": "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache"
+///         },
+///         {
+///             "operation": "GetItem",
+///             "decision": "proxy",
+///             "uri": "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/orders-cache/cache"
+///         },
+///         {
+///             "operation": "PutItem",
+///             "decision": "skip",
+///             "reason": {"reason": "denied_table", "table": "ledger"}
+///         },
+///         {
+///             "operation": "ListTables",
+///             "decision": "skip",
+///             "reason": {"reason": "control_plane", "operation": "ListTables"}
+///         }
+///     ]"#,
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DecisionRecorder(Arc<Mutex<Vec<RecordedDecision>>>);
+
+/// One entry in a [DecisionRecorder]'s log.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[non_exhaustive]
+pub struct RecordedDecision {
+    /// The DynamoDB operation, if it could be identified
+    pub operation: Option<Operation>,
+    /// What the accelerator did with it
+    #[serde(flatten)]
+    pub decision: RewriteDecision,
+}
+
+impl DecisionRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The decisions recorded so far, oldest first.
+    pub fn decisions(&self) -> Vec<RecordedDecision> {
+        self.log().clone()
+    }
+
+    /// The recorded decisions as pretty-printed JSON, the format [assert_matches](Self::assert_matches) expects.
+    ///
+    /// Use this to write a snapshot the first time.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&*self.log()).expect("decisions serialize to json")
+    }
+
+    /// Panic unless the recorded decisions match the `expected` JSON snapshot.
+    ///
+    /// The comparison ignores formatting and the order of object fields.
+    pub fn assert_matches(&self, expected: &str) {
+        let expected: serde_json::Value =
+            serde_json::from_str(expected).expect("expected decisions must be valid json");
+        let actual = serde_json::to_value(&*self.log()).expect("decisions serialize to json");
+        assert!(
+            expected == actual,
+            "recorded decisions do not match the snapshot\nexpected: {expected:#}\nrecorded: {actual:#}"
+        );
+    }
+
+    pub(crate) fn record(&self, operation: Option<Operation>, decision: RewriteDecision) {
+        self.log().push(RecordedDecision {
+            operation,
+            decision,
+        });
+    }
+
+    fn log(&self) -> std::sync::MutexGuard<'_, Vec<RecordedDecision>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
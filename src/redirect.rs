@@ -0,0 +1,289 @@
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+use crate::AcceleratorConfig;
+use crate::proxy_interceptor::with_origin_of;
+
+/// Where the proxy redirected the request, which the remaining attempts are sent to, see
+/// [follow_proxy_redirects](AcceleratorConfig::follow_proxy_redirects)
+#[derive(Debug, Clone)]
+pub(crate) struct ProxyRedirect(pub(crate) String);
+
+impl Storable for ProxyRedirect {
+    type Storer = StoreReplace<Self>;
+}
+
+impl AcceleratorConfig {
+    /// Follow a redirect from the proxy, like to another cell while a cache migrates, by retrying
+    /// the request at its `Location`.
+    ///
+    /// The SDK doesn't follow redirects for a signed request, so a redirected request fails with a
+    /// [ProxyError::Redirected](crate::ProxyError::Redirected) naming where the proxy sent it. With
+    /// this, the operation is instead retried once against that location, with every accelerator
+    /// header sent again, so the client's retry config needs to allow another attempt. A second
+    /// redirect still fails, as does one whose `Location` is missing or isn't a URI with a scheme
+    /// and host, which is never retried. A relative `Location` is taken to be on the configured
+    /// proxy host. Redirects are `301`, `302`, `303`, `307` and `308` responses. The default is
+    /// `false`.
+    ///
+    /// Since the retried request carries the Momento auth token and the signed body, only an
+    /// `https` location on the configured proxy host and port, or on one of the
+    /// [proxy_redirect_hosts](Self::proxy_redirect_hosts), is followed. A redirect anywhere else,
+    /// or to plain `http`, fails with [ProxyError::Redirected](crate::ProxyError::Redirected) as if
+    /// it weren't followed, and so does every redirect when the proxy itself is reached over
+    /// [insecure_http](Self::insecure_http).
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, ProxyError,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let moved_to = "https://api.cache.cell-us-east-1-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache";
+    /// # let sent = Arc::new(Mutex::new(Vec::new()));
+    /// # // The cache moved to another cell
+    /// # let http_client = infallible_client_fn({
+    /// #     let sent = sent.clone();
+    /// #     move |request| {
+    /// #         let uri = request.uri().to_string();
+    /// #         let x_uri = request.headers().get("x-uri").unwrap().to_str().unwrap().to_string();
+    /// #         let broken = std::str::from_utf8(request.body().bytes().unwrap())
+    /// #             .unwrap()
+    /// #             .contains("broken-redirects");
+    /// #         let response = if uri == moved_to {
+    /// #             http::Response::builder().body(r#"{"Item": {"id": {"S": "u-1"}}}"#)
+    /// #         } else if broken {
+    /// #             // No location to follow
+    /// #             http::Response::builder().status(302).body("")
+    /// #         } else {
+    /// #             http::Response::builder()
+    /// #                 .status(307)
+    /// #                 .header("location", moved_to)
+    /// #                 .body("")
+    /// #         };
+    /// #         sent.lock().unwrap().push((uri, x_uri));
+    /// #         response.unwrap()
+    /// #     }
+    /// # });
+    /// let client = |follow| {
+    ///     let config = aws_sdk_dynamodb::Config::builder()
+    /// #       .test_defaults()
+    /// #       .http_client(http_client.clone())
+    ///         .retry_config(RetryConfig::standard())
+    ///         .with_momento_accelerator(
+    ///             accelerator_config()
+    ///                 .cache_name("my-dynamo-cache")
+    ///                 .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///                 .auth_token("my-momento-auth-token")
+    ///                 .ttl(Duration::from_secs(60))
+    ///                 .follow_proxy_redirects(follow)
+    ///                 .proxy_redirect_hosts([
+    ///                     "api.cache.cell-us-east-1-1.prod.a.momentohq.com".to_string(),
+    ///                 ]),
+    ///         )
+    ///         .build();
+    ///     aws_sdk_dynamodb::Client::from_conf(config)
+    /// };
+    ///
+    /// // By default, the redirect is an error
+    /// let response = AcceleratorResponse::new();
+    /// let result = client(false)
+    ///     .get_item()
+    ///     .table_name("users")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await;
+    /// assert!(result.is_err());
+    /// assert_eq!(
+    ///     response.proxy_error(),
+    ///     Some(ProxyError::Redirected {
+    ///         location: moved_to.to_string(),
+    ///     }),
+    /// );
+    /// # assert_eq!(sent.lock().unwrap().len(), 1);
+    ///
+    /// // Following it, the request is sent again to the new cell
+    /// # sent.lock().unwrap().clear();
+    /// let output = client(true)
+    ///     .get_item()
+    ///     .table_name("users")
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    /// assert!(output.item.is_some());
+    /// # let dynamo = "https://dynamodb.us-west-2.amazonaws.com/".to_string();
+    /// # assert_eq!(
+    /// #     *sent.lock().unwrap(),
+    /// #     [
+    /// #         (
+    /// #             "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache"
+    /// #                 .to_string(),
+    /// #             dynamo.clone(),
+    /// #         ),
+    /// #         (moved_to.to_string(), dynamo),
+    /// #     ],
+    /// # );
+    ///
+    /// // A redirect without a location fails, even when following
+    /// # sent.lock().unwrap().clear();
+    /// let response = AcceleratorResponse::new();
+    /// let result = client(true)
+    ///     .get_item()
+    ///     .table_name("broken-redirects")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await;
+    /// assert!(result.is_err());
+    /// assert_eq!(
+    ///     response.proxy_error(),
+    ///     Some(ProxyError::Redirected {
+    ///         location: String::new(),
+    ///     }),
+    /// );
+    /// # assert_eq!(sent.lock().unwrap().len(), 1);
+    /// # }
+    /// ```
+    pub fn follow_proxy_redirects(mut self, enabled: bool) -> Self {
+        self.follow_proxy_redirects = enabled;
+        self
+    }
+
+    /// Also follow redirects to `hosts` with
+    /// [follow_proxy_redirects](Self::follow_proxy_redirects), besides the configured proxy host.
+    ///
+    /// Each host is compared without case and only on the default `https` port, like
+    /// `api.cache.cell-us-east-1-1.prod.a.momentohq.com` for another cell a cache may move to.
+    /// With [proxy_host_from_endpoint](Self::proxy_host_from_endpoint), list the endpoint's host
+    /// here for its redirects to be followed. Calling this more than once allows the hosts of
+    /// each call.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, ProxyError,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let sent = Arc::new(Mutex::new(Vec::new()));
+    /// # // Redirects to wherever the table name says
+    /// # let http_client = infallible_client_fn({
+    /// #     let sent = sent.clone();
+    /// #     move |request| {
+    /// #         sent.lock().unwrap().push(request.uri().to_string());
+    /// #         let body = std::str::from_utf8(request.body().bytes().unwrap()).unwrap();
+    /// #         let location = if body.contains("to-http") {
+    /// #             "http://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache"
+    /// #         } else {
+    /// #             "https://proxy.example.com/ddb/my-dynamo-cache/cache"
+    /// #         };
+    /// #         http::Response::builder()
+    /// #             .status(307)
+    /// #             .header("location", location)
+    /// #             .body("")
+    /// #             .unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .retry_config(RetryConfig::standard())
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .follow_proxy_redirects(true)
+    ///             .proxy_redirect_hosts([
+    ///                 "api.cache.cell-us-east-1-1.prod.a.momentohq.com".to_string(),
+    ///             ]),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let redirected_to = async |table| {
+    ///     let response = AcceleratorResponse::new();
+    ///     let result = client
+    ///         .get_item()
+    ///         .table_name(table)
+    ///         .customize()
+    ///         .capture_accelerator_response(&response)
+    ///         .send()
+    ///         .await;
+    ///     assert!(result.is_err());
+    ///     match response.proxy_error() {
+    ///         Some(ProxyError::Redirected { location }) => location,
+    ///         error => panic!("expected a redirect: {error:?}"),
+    ///     }
+    /// };
+    ///
+    /// // A host that isn't allowed isn't followed, so the token never reaches it
+    /// assert_eq!(
+    ///     redirected_to("to-another-host").await,
+    ///     "https://proxy.example.com/ddb/my-dynamo-cache/cache",
+    /// );
+    /// // Nor is plain http, even on the proxy host
+    /// assert_eq!(
+    ///     redirected_to("to-http").await,
+    ///     "http://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/my-dynamo-cache/cache",
+    /// );
+    /// # assert!(sent.lock().unwrap().iter().all(|uri| uri.starts_with(
+    /// #     "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/"
+    /// # )));
+    /// # assert_eq!(sent.lock().unwrap().len(), 2);
+    /// # }
+    /// ```
+    pub fn proxy_redirect_hosts(mut self, hosts: impl IntoIterator<Item = String>) -> Self {
+        self.proxy_redirect_hosts
+            .extend(hosts.into_iter().map(|host| host.to_ascii_lowercase()));
+        self
+    }
+
+    /// The absolute URI a redirect to `location` can be followed to, or `None` if it has no
+    /// scheme and host or isn't an `https` URI on the proxy host or one of the
+    /// [proxy_redirect_hosts](Self::proxy_redirect_hosts). A relative `location` is taken to be
+    /// on the configured proxy host.
+    pub(crate) fn redirect_target(&self, location: &str) -> Option<String> {
+        let target = if location.starts_with('/') {
+            with_origin_of(location, &self.uri)
+        } else {
+            location.to_string()
+        };
+        let uri = target.parse::<http::Uri>().ok()?;
+        let host = uri.host().filter(|host| !host.is_empty())?;
+        let allowed = self.is_proxy_host(&uri)
+            || (self
+                .proxy_redirect_hosts
+                .contains(&host.to_ascii_lowercase())
+                && uri.port_u16().is_none_or(|port| port == 443));
+        (self.scheme == "https" && uri.scheme_str() == Some("https") && allowed).then_some(target)
+    }
+}
+
+/// Where `response` redirects the request, if it is a redirect, or an empty location if it
+/// didn't say.
+pub(crate) fn redirect_location(response: &HttpResponse) -> Option<String> {
+    matches!(response.status().as_u16(), 301 | 302 | 303 | 307 | 308).then(|| {
+        response
+            .headers()
+            .get("location")
+            .unwrap_or_default()
+            .to_string()
+    })
+}
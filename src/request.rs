@@ -0,0 +1,700 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use aws_sdk_dynamodb::client::customize::CustomizableOperation;
+use aws_sdk_dynamodb::config::interceptors::BeforeSerializationInterceptorContextRef;
+use aws_sdk_dynamodb::config::{ConfigBag, Intercept};
+use aws_sdk_dynamodb::error::BoxError;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+use crate::{AcceleratorResponse, AccessHint, Operation, OperationInput, Priority};
+
+/// Accelerator settings that apply to a single operation rather than the whole client.
+///
+/// These are stashed in the operation's config bag before execution and read back by the
+/// [ProxyInterceptor](crate::ProxyInterceptor) when it rewrites the request.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RequestSettings {
+    pub(crate) if_version: Option<String>,
+    pub(crate) response: Option<AcceleratorResponse>,
+    pub(crate) dynamic_ttl: Option<DynamicTtl>,
+    pub(crate) omit_ttl: bool,
+    /// Set by [invalidate_keys](crate::invalidate_keys)
+    pub(crate) evict: bool,
+    /// Set by [invalidate_partition](crate::invalidate_partition)
+    pub(crate) evict_partition: bool,
+    /// Set by [invalidate_by_tag](crate::invalidate_by_tag)
+    pub(crate) evict_tag: Option<String>,
+    /// The write a [cdc_notify](crate::AcceleratorConfig::cdc_notify) notification is for
+    pub(crate) cdc_notify: Option<Operation>,
+    /// Sent with the tags of [cache_tags_fn](crate::AcceleratorConfig::cache_tags_fn)
+    pub(crate) cache_tags: Vec<String>,
+    pub(crate) cache: Option<String>,
+    pub(crate) priority: Option<Priority>,
+    pub(crate) tenant_id: Option<String>,
+    pub(crate) access_hint: Option<AccessHint>,
+    pub(crate) deadline: Option<Instant>,
+}
+
+/// Computes the TTL for an operation from its input.
+#[derive(Clone)]
+pub(crate) struct DynamicTtl(Arc<dyn Fn(&OperationInput) -> Duration + Send + Sync>);
+
+impl DynamicTtl {
+    pub(crate) fn ttl(&self, input: &OperationInput) -> Duration {
+        (self.0)(input)
+    }
+}
+
+impl Debug for DynamicTtl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DynamicTtl").finish_non_exhaustive()
+    }
+}
+
+impl Storable for RequestSettings {
+    type Storer = StoreReplace<Self>;
+}
+
+/// Several per-operation accelerator settings at once, for
+/// [with_accelerator_overrides](AcceleratorRequestExt::with_accelerator_overrides).
+///
+/// Each setting matches one of the [AcceleratorRequestExt] methods, and settings left unset
+/// keep the configuration's behavior. Build one once and reuse it for every operation that
+/// needs the same treatment.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::capture_request;
+/// use momento_ddb_interceptor::{
+///     AcceleratorRequestExt, MomentoAccelerator, Priority, RequestOverrides, accelerator_config,
+/// };
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let (http_client, request) = capture_request(None);
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let checkout = RequestOverrides::new()
+///     .ttl(Duration::from_secs(5))
+///     .cache("checkout-cache")
+///     .priority(Priority::High)
+///     .tenant_id("tenant-a")
+///     .if_version("v3");
+/// let _ = client
+///     .get_item()
+///     .table_name("carts")
+///     .customize()
+///     .with_accelerator_overrides(checkout)
+///     .send()
+///     .await;
+///
+/// let request = request.expect_request();
+/// assert_eq!(request.uri(), "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/ddb/checkout-cache/cache");
+/// let headers = request.headers();
+/// assert_eq!(headers.get("x-ttl-millis"), Some("5000"));
+/// assert_eq!(headers.get("x-priority"), Some("high"));
+/// assert_eq!(headers.get("x-tenant-id"), Some("tenant-a"));
+/// assert_eq!(headers.get("x-if-version"), Some("v3"));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestOverrides {
+    ttl: Option<TtlOverride>,
+    cache: Option<String>,
+    priority: Option<Priority>,
+    tenant_id: Option<String>,
+    if_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TtlOverride {
+    Ttl(Duration),
+    Omit,
+}
+
+impl RequestOverrides {
+    /// Overrides that don't change anything yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Let the proxy cache the response for `ttl`, still limited by
+    /// [min_ttl](crate::AcceleratorConfig::min_ttl) and
+    /// [max_ttl](crate::AcceleratorConfig::max_ttl).
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(TtlOverride::Ttl(ttl));
+        self
+    }
+
+    /// Send no TTL, like [without_accelerator_ttl](AcceleratorRequestExt::without_accelerator_ttl).
+    pub fn without_ttl(mut self) -> Self {
+        self.ttl = Some(TtlOverride::Omit);
+        self
+    }
+
+    /// Use `cache_name`, like [with_accelerator_cache](AcceleratorRequestExt::with_accelerator_cache).
+    pub fn cache(mut self, cache_name: impl Into<String>) -> Self {
+        self.cache = Some(cache_name.into());
+        self
+    }
+
+    /// Send `priority`, like [with_priority](AcceleratorRequestExt::with_priority).
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Send `tenant_id`, like [with_tenant_id](AcceleratorRequestExt::with_tenant_id).
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Send `version`, like [if_version](AcceleratorRequestExt::if_version).
+    pub fn if_version(mut self, version: impl Into<String>) -> Self {
+        self.if_version = Some(version.into());
+        self
+    }
+
+    fn apply(&self, settings: &mut RequestSettings) {
+        match self.ttl {
+            Some(TtlOverride::Ttl(ttl)) => {
+                settings.dynamic_ttl = Some(DynamicTtl(Arc::new(move |_| ttl)));
+                settings.omit_ttl = false;
+            }
+            Some(TtlOverride::Omit) => settings.omit_ttl = true,
+            None => {}
+        }
+        if let Some(cache_name) = &self.cache {
+            settings.cache = Some(cache_name.clone());
+        }
+        if let Some(priority) = self.priority {
+            settings.priority = Some(priority);
+        }
+        if let Some(tenant_id) = &self.tenant_id {
+            settings.tenant_id = Some(tenant_id.clone());
+        }
+        if let Some(version) = &self.if_version {
+            settings.if_version = Some(version.clone());
+        }
+    }
+}
+
+/// Extension trait for customizing how the Momento accelerator handles a single DynamoDB operation.
+///
+/// These methods are available on any operation after calling `.customize()`.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use momento_ddb_interceptor::{
+///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, accelerator_config,
+/// };
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let sent_version = Arc::new(Mutex::new(None));
+/// # let http_client = infallible_client_fn({
+/// #     let sent_version = sent_version.clone();
+/// #     move |request| {
+/// #         *sent_version.lock().unwrap() = request
+/// #             .headers()
+/// #             .get("x-if-version")
+/// #             .map(|value| value.to_str().unwrap().to_string());
+/// #         http::Response::builder()
+/// #             .header("x-version-matched", "true")
+/// #             .body("{}")
+/// #             .unwrap()
+/// #     }
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60)),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+///
+/// let response = AcceleratorResponse::new();
+/// client
+///     .get_item()
+///     .table_name("my-table")
+///     .customize()
+///     .if_version("v3")
+///     .capture_accelerator_response(&response)
+///     .send()
+///     .await
+///     .expect("get item");
+///
+/// # assert_eq!(sent_version.lock().unwrap().as_deref(), Some("v3"));
+/// assert_eq!(response.version_matched(), Some(true));
+/// # }
+/// ```
+pub trait AcceleratorRequestExt {
+    /// Send the version of the item you already hold as `x-if-version`.
+    ///
+    /// When the cached item still has this version, the proxy can confirm it is current instead
+    /// of re-sending the payload. Use [capture_accelerator_response](Self::capture_accelerator_response)
+    /// to find out whether it matched.
+    fn if_version(self, version: impl Into<String>) -> Self;
+
+    /// Record what the accelerator observed about this operation's response into `response`.
+    ///
+    /// Inspect `response` after the operation completes.
+    fn capture_accelerator_response(self, response: &AcceleratorResponse) -> Self;
+
+    /// Choose this operation's TTL from its input, instead of using the configured TTL.
+    ///
+    /// `ttl` is called when the request is sent to the proxy, with what the accelerator read
+    /// from the request, so it can depend on things like the item's key.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::types::AttributeValue;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{AcceleratorRequestExt, MomentoAccelerator, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let ttls = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let ttls = ttls.clone();
+    /// #     move |request| {
+    /// #         ttls.lock().unwrap().push(
+    /// #             request.headers()["x-ttl-millis"].to_str().unwrap().to_string(),
+    /// #         );
+    /// #         http::Response::builder().body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// for user in ["config#global", "user#42"] {
+    ///     client
+    ///         .get_item()
+    ///         .table_name("my-table")
+    ///         .key("pk", AttributeValue::S(user.into()))
+    ///         .customize()
+    ///         .with_dynamic_ttl(|input| {
+    ///             // Configuration rarely changes, so it can be cached for longer
+    ///             let key = input.key().unwrap_or_default();
+    ///             match key.get("pk").and_then(|pk| pk.as_s().ok()) {
+    ///                 Some(pk) if pk.starts_with("config#") => Duration::from_secs(600),
+    ///                 _ => Duration::from_secs(5),
+    ///             }
+    ///         })
+    ///         .send()
+    ///         .await
+    ///         .expect("get item");
+    /// }
+    ///
+    /// assert_eq!(*ttls.lock().unwrap(), ["600000", "5000"]);
+    /// # }
+    /// ```
+    fn with_dynamic_ttl(
+        self,
+        ttl: impl Fn(&OperationInput) -> Duration + Send + Sync + 'static,
+    ) -> Self;
+
+    /// Don't send a TTL header for this operation, so the proxy uses its own TTL.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{AcceleratorRequestExt, MomentoAccelerator, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let ttls = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let ttls = ttls.clone();
+    /// #     move |request| {
+    /// #         ttls.lock().unwrap().push(
+    /// #             request
+    /// #                 .headers()
+    /// #                 .get("x-ttl-millis")
+    /// #                 .map(|value| value.to_str().unwrap().to_string()),
+    /// #         );
+    /// #         http::Response::builder().body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// client.get_item().table_name("my-table").send().await.expect("get item");
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .without_accelerator_ttl()
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    ///
+    /// assert_eq!(*ttls.lock().unwrap(), [Some("60000".to_string()), None]);
+    /// # }
+    /// ```
+    fn without_accelerator_ttl(self) -> Self;
+
+    /// Send this operation to the cache named `cache_name`, instead of the one the configuration
+    /// would choose.
+    ///
+    /// This overrides both the configured cache and any
+    /// [table_cache](crate::AcceleratorConfig::table_cache) mapping, which is useful for routing
+    /// each tenant to its own cache. The name is percent-encoded into the proxy path.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{AcceleratorRequestExt, MomentoAccelerator, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let paths = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let paths = paths.clone();
+    /// #     move |request| {
+    /// #         paths.lock().unwrap().push(request.uri().path().to_string());
+    /// #         http::Response::builder().body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// for tenant in ["tenant-a", "tenant-b"] {
+    ///     client
+    ///         .get_item()
+    ///         .table_name("orders")
+    ///         .customize()
+    ///         .with_accelerator_cache(format!("{tenant}-cache"))
+    ///         .send()
+    ///         .await
+    ///         .expect("get item");
+    /// }
+    ///
+    /// assert_eq!(
+    ///     *paths.lock().unwrap(),
+    ///     ["/ddb/tenant-a-cache/cache", "/ddb/tenant-b-cache/cache"],
+    /// );
+    /// # }
+    /// ```
+    fn with_accelerator_cache(self, cache_name: impl Into<String>) -> Self;
+
+    /// Send `priority` as `x-priority` for this operation, instead of the
+    /// [default_priority](crate::AcceleratorConfig::default_priority).
+    fn with_priority(self, priority: Priority) -> Self;
+
+    /// Send `tenant_id` as `x-tenant-id` for this operation, instead of the
+    /// [tenant_id](crate::AcceleratorConfig::tenant_id) of the configuration.
+    fn with_tenant_id(self, tenant_id: impl Into<String>) -> Self;
+
+    /// Send `hint` as `x-access-hint` for this operation, instead of the one the configuration
+    /// would choose.
+    ///
+    /// See [access_hint_thresholds](crate::AcceleratorConfig::access_hint_thresholds) for an
+    /// example.
+    fn with_access_hint(self, hint: AccessHint) -> Self;
+
+    /// Tag the items this operation caches with `tags`, in addition to any from the
+    /// [cache_tags_fn](crate::AcceleratorConfig::cache_tags_fn) of the configuration.
+    ///
+    /// The tags are sent as `x-cache-tags`, and [invalidate_by_tag](crate::invalidate_by_tag)
+    /// evicts every item cached with a tag.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::capture_request;
+    /// use momento_ddb_interceptor::{AcceleratorRequestExt, MomentoAccelerator, accelerator_config};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let (http_client, request) = capture_request(None);
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let _ = client
+    ///     .get_item()
+    ///     .table_name("orders")
+    ///     .customize()
+    ///     .with_cache_tags(["customer:c-42", "region/eu"])
+    ///     .send()
+    ///     .await;
+    ///
+    /// let request = request.expect_request();
+    /// assert_eq!(
+    ///     request.headers().get("x-cache-tags"),
+    ///     Some("customer%3Ac-42,region%2Feu"),
+    /// );
+    /// # }
+    /// ```
+    fn with_cache_tags(self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self;
+
+    /// Send the time left until `deadline` as `x-deadline-millis`, so the proxy can give up on
+    /// a request the caller will no longer wait for.
+    ///
+    /// This is for deadlines that come from the caller, like one propagated from an incoming
+    /// request, rather than the SDK's operation timeout. The time left is measured each time
+    /// the request is sent to the proxy, so a retry carries less than the first attempt. A
+    /// deadline that has already passed is sent as `0`.
+    ///
+    /// The deadline is read against the monotonic clock once, when the operation starts, and from
+    /// then on the time left is measured with the configured [Clock](crate::Clock).
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, Clock, MomentoAccelerator, accelerator_config,
+    /// };
+    /// use std::time::{Duration, Instant, SystemTime};
+    ///
+    /// #[derive(Debug, Clone)]
+    /// struct FakeClock(Arc<Mutex<SystemTime>>);
+    ///
+    /// impl Clock for FakeClock {
+    ///     fn now(&self) -> SystemTime {
+    ///         *self.0.lock().unwrap()
+    ///     }
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let clock = FakeClock(Arc::new(Mutex::new(SystemTime::UNIX_EPOCH)));
+    /// # let deadlines = Arc::new(Mutex::new(Vec::new()));
+    /// # // The proxy takes 300ms to fail the first attempt
+    /// # let http_client = infallible_client_fn({
+    /// #     let (clock, deadlines) = (clock.clone(), deadlines.clone());
+    /// #     move |request| {
+    /// #         let mut deadlines = deadlines.lock().unwrap();
+    /// #         let deadline = request.headers().get("x-deadline-millis").unwrap();
+    /// #         deadlines.push(deadline.to_str().unwrap().parse::<u64>().unwrap());
+    /// #         *clock.0.lock().unwrap() += Duration::from_millis(300);
+    /// #         let status = if deadlines.len() == 1 { 500 } else { 200 };
+    /// #         http::Response::builder().status(status).body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    /// #   .retry_config(RetryConfig::standard().with_initial_backoff(Duration::from_millis(1)))
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60))
+    ///             .clock(clock.clone()),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let deadline = Instant::now() + Duration::from_millis(800);
+    /// client
+    ///     .get_item()
+    ///     .table_name("orders")
+    ///     .customize()
+    ///     .with_accelerator_deadline(deadline)
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    ///
+    /// // The retry carries 300ms less than the first attempt, which had up to 800ms
+    /// # let deadlines = deadlines.lock().unwrap();
+    /// let (first, retry) = (deadlines[0], deadlines[1]);
+    /// assert!(first <= 800, "{first}");
+    /// assert_eq!(retry, first - 300);
+    /// # }
+    /// ```
+    fn with_accelerator_deadline(self, deadline: Instant) -> Self;
+
+    /// Apply every setting of `overrides` to this operation.
+    ///
+    /// See [RequestOverrides] for an example.
+    fn with_accelerator_overrides(self, overrides: RequestOverrides) -> Self;
+}
+
+impl<T, E, B> AcceleratorRequestExt for CustomizableOperation<T, E, B> {
+    fn if_version(self, version: impl Into<String>) -> Self {
+        let version = version.into();
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.if_version = Some(version.clone())
+        }))
+    }
+
+    fn capture_accelerator_response(self, response: &AcceleratorResponse) -> Self {
+        let response = response.clone();
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.response = Some(response.clone())
+        }))
+    }
+
+    fn with_dynamic_ttl(
+        self,
+        ttl: impl Fn(&OperationInput) -> Duration + Send + Sync + 'static,
+    ) -> Self {
+        let ttl = DynamicTtl(Arc::new(ttl));
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.dynamic_ttl = Some(ttl.clone())
+        }))
+    }
+
+    fn without_accelerator_ttl(self) -> Self {
+        self.interceptor(UpdateRequestSettings::new(|settings| {
+            settings.omit_ttl = true
+        }))
+    }
+
+    fn with_accelerator_cache(self, cache_name: impl Into<String>) -> Self {
+        let cache_name = cache_name.into();
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.cache = Some(cache_name.clone())
+        }))
+    }
+
+    fn with_priority(self, priority: Priority) -> Self {
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.priority = Some(priority)
+        }))
+    }
+
+    fn with_tenant_id(self, tenant_id: impl Into<String>) -> Self {
+        let tenant_id = tenant_id.into();
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.tenant_id = Some(tenant_id.clone())
+        }))
+    }
+
+    fn with_access_hint(self, hint: AccessHint) -> Self {
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.access_hint = Some(hint)
+        }))
+    }
+
+    fn with_cache_tags(self, tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let tags: Vec<String> = tags.into_iter().map(Into::into).collect();
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.cache_tags.extend(tags.iter().cloned())
+        }))
+    }
+
+    fn with_accelerator_deadline(self, deadline: Instant) -> Self {
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            settings.deadline = Some(deadline)
+        }))
+    }
+
+    fn with_accelerator_overrides(self, overrides: RequestOverrides) -> Self {
+        self.interceptor(UpdateRequestSettings::new(move |settings| {
+            overrides.apply(settings)
+        }))
+    }
+}
+
+/// Operation interceptor that applies one change to the operation's [RequestSettings].
+pub(crate) struct UpdateRequestSettings(Box<dyn Fn(&mut RequestSettings) + Send + Sync>);
+
+impl UpdateRequestSettings {
+    pub(crate) fn new(update: impl Fn(&mut RequestSettings) + Send + Sync + 'static) -> Self {
+        Self(Box::new(update))
+    }
+}
+
+impl Debug for UpdateRequestSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UpdateRequestSettings")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Intercept for UpdateRequestSettings {
+    fn name(&self) -> &'static str {
+        "MomentoRequestSettings"
+    }
+
+    fn read_before_execution(
+        &self,
+        _context: &BeforeSerializationInterceptorContextRef<'_>,
+        cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        // Several settings may be attached to one operation, so build on whatever is already there
+        let mut settings = cfg.load::<RequestSettings>().cloned().unwrap_or_default();
+        (self.0)(&mut settings);
+        cfg.interceptor_state().store_put(settings);
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use aws_sdk_dynamodb::config::http::HttpResponse;
+
+use crate::{ProxyError, SkipReason};
+
+/// Details the accelerator observed about the response to a single operation.
+///
+/// Create one, attach it with
+/// [capture_accelerator_response](crate::AcceleratorRequestExt::capture_accelerator_response),
+/// and read it after the operation completes. Clones share the same details.
+#[derive(Debug, Clone, Default)]
+pub struct AcceleratorResponse(Arc<Mutex<ResponseDetails>>);
+
+#[derive(Debug, Default)]
+struct ResponseDetails {
+    version_matched: Option<bool>,
+    suggested_ttl: Option<Duration>,
+    cache_status: Option<CacheStatus>,
+    cache_age: Option<Duration>,
+    proxy_error: Option<ProxyError>,
+    skip_reason: Option<SkipReason>,
+    proxy_uri: Option<String>,
+    proxy_headers: Option<http::HeaderMap>,
+    proxy_latency: Option<Duration>,
+}
+
+/// Whether a response came from the cache, see [AcceleratorResponse::cache_status].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CacheStatus {
+    /// Served from the proxy's cache or the [local cache](crate::AcceleratorConfig::local_cache).
+    Hit,
+    /// The proxy read the response from DynamoDB.
+    Miss,
+    /// The request went directly to DynamoDB, see [AcceleratorResponse::skip_reason].
+    Bypassed,
+    /// The proxy didn't say whether it served the response from its cache.
+    Unknown,
+}
+
+/// How the accelerator handled an operation, in one value, see [AcceleratorResponse::status].
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// use momento_ddb_interceptor::{
+///     AcceleratorRequestExt, AcceleratorResponse, AcceleratorStatus, MomentoAccelerator,
+///     ProxyError, SkipReason, accelerator_config,
+/// };
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # // The proxy answers for each table differently
+/// # let http_client = infallible_client_fn(|request| {
+/// #     let body = std::str::from_utf8(request.body().bytes().unwrap()).unwrap();
+/// #     let response = http::Response::builder();
+/// #     let response = if body.contains("hot-table") {
+/// #         response.header("x-momento-proxy", "1").header("x-cache-status", "hit")
+/// #     } else if body.contains("cold-table") {
+/// #         response.header("x-momento-proxy", "1").header("x-cache-status", "miss")
+/// #     } else {
+/// #         response
+/// #     };
+/// #     response.body("{}").unwrap()
+/// # });
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60))
+///             .deny_tables(["audit-table".to_string()])
+///             .verify_proxy_response(true),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+/// let status = async |table: &str| {
+///     let response = AcceleratorResponse::new();
+///     let _ = client
+///         .get_item()
+///         .table_name(table)
+///         .customize()
+///         .capture_accelerator_response(&response)
+///         .send()
+///         .await;
+///     response.status()
+/// };
+///
+/// assert_eq!(status("hot-table").await, AcceleratorStatus::Hit);
+/// assert_eq!(status("cold-table").await, AcceleratorStatus::Miss);
+/// assert_eq!(
+///     status("audit-table").await,
+///     AcceleratorStatus::Bypassed(SkipReason::DeniedTable {
+///         table: "audit-table".to_string(),
+///     }),
+/// );
+/// // A response that didn't come from the proxy
+/// assert!(matches!(
+///     status("other-table").await,
+///     AcceleratorStatus::ProxyError(ProxyError::Unverified { .. }),
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum AcceleratorStatus {
+    /// Served from the proxy's cache or the [local cache](crate::AcceleratorConfig::local_cache).
+    Hit,
+    /// The proxy read the response from DynamoDB.
+    Miss,
+    /// The request went directly to DynamoDB, for this reason.
+    Bypassed(SkipReason),
+    /// The request failed at the proxy.
+    ProxyError(ProxyError),
+    /// The proxy didn't say whether it served the response from its cache, or the operation
+    /// hasn't completed.
+    Unknown,
+}
+
+impl AcceleratorResponse {
+    /// Create an empty response handle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the proxy confirmed that the version sent with
+    /// [if_version](crate::AcceleratorRequestExt::if_version) is still current.
+    ///
+    /// `None` when the proxy did not report a version check.
+    pub fn version_matched(&self) -> Option<bool> {
+        self.details().version_matched
+    }
+
+    /// Whether the response came from the cache, as the proxy reported in `x-cache-status`.
+    ///
+    /// When the operation was retried, this describes the last attempt. See
+    /// [AcceleratedDynamoClient](crate::AcceleratedDynamoClient) for an example.
+    pub fn cache_status(&self) -> CacheStatus {
+        let details = self.details();
+        if details.skip_reason.is_some() {
+            return CacheStatus::Bypassed;
+        }
+        details.cache_status.unwrap_or(CacheStatus::Unknown)
+    }
+
+    /// How the accelerator handled the operation: whether it was served from the cache, skipped
+    /// the proxy, or failed at it.
+    ///
+    /// This combines [cache_status](Self::cache_status), [skip_reason](Self::skip_reason) and
+    /// [proxy_error](Self::proxy_error). When the operation was retried, this describes the
+    /// last attempt. See [AcceleratorStatus] for an example.
+    pub fn status(&self) -> AcceleratorStatus {
+        let details = self.details();
+        if let Some(skip_reason) = &details.skip_reason {
+            return AcceleratorStatus::Bypassed(skip_reason.clone());
+        }
+        if let Some(proxy_error) = &details.proxy_error {
+            return AcceleratorStatus::ProxyError(proxy_error.clone());
+        }
+        match details.cache_status {
+            Some(CacheStatus::Hit) => AcceleratorStatus::Hit,
+            Some(CacheStatus::Miss) => AcceleratorStatus::Miss,
+            _ => AcceleratorStatus::Unknown,
+        }
+    }
+
+    /// The TTL the response suggested in `x-suggested-ttl-millis`, for adapting the TTL of later
+    /// requests, like with [with_dynamic_ttl](crate::AcceleratorRequestExt::with_dynamic_ttl).
+    ///
+    /// `None` when the response did not suggest one.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let http_client = infallible_client_fn(|_| {
+    /// #     http::Response::builder()
+    /// #         .header("x-suggested-ttl-millis", "90000")
+    /// #         .body("{}")
+    /// #         .unwrap()
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    ///
+    /// assert_eq!(response.suggested_ttl(), Some(Duration::from_secs(90)));
+    /// # }
+    /// ```
+    pub fn suggested_ttl(&self) -> Option<Duration> {
+        self.details().suggested_ttl
+    }
+
+    /// How long ago the cached response was stored, as the proxy reported in
+    /// `x-cache-age-millis`.
+    ///
+    /// `None` when the response didn't come from the proxy's cache or the proxy did not report
+    /// its age. When the operation was retried, this describes the last attempt.
+    pub fn cache_age(&self) -> Option<Duration> {
+        self.details().cache_age
+    }
+
+    /// The error reported by the proxy, if the request failed at the proxy.
+    ///
+    /// The proxy's error responses are only parsed with the `serde` feature, while a
+    /// [timeout](ProxyError::Timeout) is always reported.
+    pub fn proxy_error(&self) -> Option<ProxyError> {
+        self.details().proxy_error.clone()
+    }
+
+    /// Why the request was sent directly to DynamoDB, or `None` if it went through the proxy.
+    ///
+    /// When the operation was retried, this describes the last attempt.
+    pub fn skip_reason(&self) -> Option<SkipReason> {
+        self.details().skip_reason.clone()
+    }
+
+    /// The proxy URI the request was sent to, or `None` if it went directly to DynamoDB.
+    ///
+    /// This shows which cache [table_cache](crate::AcceleratorConfig::table_cache) chose. An
+    /// auth token sent [in the query](crate::AcceleratorConfig::auth_in_query) is redacted. When
+    /// the operation was retried, this describes the last attempt.
+    pub fn proxy_uri(&self) -> Option<String> {
+        self.details().proxy_uri.clone()
+    }
+
+    /// Every header the proxy responded with, or `None` if the request went directly to DynamoDB.
+    ///
+    /// Only captured with [capture_proxy_headers](crate::AcceleratorConfig::capture_proxy_headers).
+    /// When the operation was retried, this describes the last attempt.
+    pub fn proxy_headers(&self) -> Option<http::HeaderMap> {
+        self.details().proxy_headers.clone()
+    }
+
+    /// How long the proxy took to respond, or `None` if the request went directly to DynamoDB.
+    ///
+    /// This is only the time from sending the request to the proxy until its response was read,
+    /// measured with the configured [Clock](crate::Clock), so unlike timing the whole operation
+    /// it leaves out signing, retries and deserializing the output. When the operation was
+    /// retried, this describes the last attempt.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # // A proxy that takes 50ms to answer
+    /// # let http_client = infallible_client_fn(|_| {
+    /// #     std::thread::sleep(Duration::from_millis(50));
+    /// #     http::Response::builder()
+    /// #         .header("x-cache-status", "hit")
+    /// #         .body("{}")
+    /// #         .unwrap()
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    ///
+    /// let latency = response.proxy_latency().expect("proxied");
+    /// assert!(latency >= Duration::from_millis(50), "{latency:?}");
+    /// assert!(latency < Duration::from_secs(5), "{latency:?}");
+    /// # }
+    /// ```
+    pub fn proxy_latency(&self) -> Option<Duration> {
+        self.details().proxy_latency
+    }
+
+    /// Record that the current attempt skipped the proxy, and why.
+    pub(crate) fn record_skip(&self, skip_reason: SkipReason) {
+        let mut details = self.details();
+        details.skip_reason = Some(skip_reason);
+        details.proxy_uri = None;
+        details.proxy_headers = None;
+        details.proxy_latency = None;
+        details.cache_status = None;
+        details.cache_age = None;
+    }
+
+    /// Record that the current attempt was served from the local cache.
+    pub(crate) fn record_local_hit(&self) {
+        let mut details = self.details();
+        details.skip_reason = None;
+        details.proxy_uri = None;
+        details.proxy_headers = None;
+        details.proxy_latency = None;
+        details.cache_status = Some(CacheStatus::Hit);
+        details.cache_age = None;
+    }
+
+    /// Record that the current attempt was sent to the proxy at `uri`.
+    pub(crate) fn record_proxied(&self, uri: &str) {
+        let mut details = self.details();
+        details.skip_reason = None;
+        details.proxy_uri = Some(uri.to_string());
+        details.proxy_headers = None;
+        details.proxy_latency = None;
+        details.cache_status = None;
+        details.cache_age = None;
+    }
+
+    /// Record that the proxy responded to the current attempt after `latency`.
+    pub(crate) fn record_proxy_latency(&self, latency: Duration) {
+        self.details().proxy_latency = Some(latency);
+    }
+
+    /// Capture the accelerator headers from the response.
+    pub(crate) fn record(&self, response: &HttpResponse) {
+        let mut details = self.details();
+        details.version_matched = response
+            .headers()
+            .get("x-version-matched")
+            .map(|matched| matched.eq_ignore_ascii_case("true"));
+        details.cache_status = response.headers().get("x-cache-status").and_then(|status| {
+            match status.to_ascii_lowercase().as_str() {
+                "hit" => Some(CacheStatus::Hit),
+                "miss" => Some(CacheStatus::Miss),
+                _ => None,
+            }
+        });
+        details.suggested_ttl = response
+            .headers()
+            .get("x-suggested-ttl-millis")
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis);
+        details.cache_age = response
+            .headers()
+            .get("x-cache-age-millis")
+            .filter(|_| details.cache_status == Some(CacheStatus::Hit))
+            .and_then(|millis| millis.trim().parse().ok())
+            .map(Duration::from_millis);
+    }
+
+    /// Keep a copy of every header on the proxy's response.
+    pub(crate) fn record_proxy_headers(&self, response: &HttpResponse) {
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    http::HeaderName::from_bytes(name.as_bytes()).ok()?,
+                    http::HeaderValue::from_str(value).ok()?,
+                ))
+            })
+            .collect();
+        self.details().proxy_headers = Some(headers);
+    }
+
+    /// Record that the proxied attempt failed with `proxy_error`.
+    pub(crate) fn record_proxy_error(&self, proxy_error: ProxyError) {
+        self.details().proxy_error = Some(proxy_error);
+    }
+
+    /// Capture the parts of the response that need its body, once it has been read.
+    #[cfg_attr(not(feature = "serde"), allow(unused_variables))]
+    pub(crate) fn record_body(&self, response: &HttpResponse) {
+        #[cfg(feature = "serde")]
+        {
+            self.details().proxy_error = ProxyError::from_response(response);
+        }
+    }
+
+    fn details(&self) -> std::sync::MutexGuard<'_, ResponseDetails> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
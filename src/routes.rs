@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crate::Operation;
+
+/// Operation and table combinations to send directly to DynamoDB, changeable while clients are
+/// running. See [AcceleratorConfig::disabled_routes](crate::AcceleratorConfig::disabled_routes).
+///
+/// Clones share the same routes, so one kept by an admin endpoint or a config watcher takes
+/// effect for every client configured with another.
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// # use std::sync::{Arc, Mutex};
+/// use momento_ddb_interceptor::{DisabledRoutes, MomentoAccelerator, Operation, accelerator_config};
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// # let proxied = Arc::new(Mutex::new(Vec::new()));
+/// # let http_client = infallible_client_fn({
+/// #     let proxied = proxied.clone();
+/// #     move |request| {
+/// #         let to_proxy = request.uri().host() == Some("api.cache.cell-us-west-2-1.prod.a.momentohq.com");
+/// #         proxied.lock().unwrap().push(to_proxy);
+/// #         http::Response::builder().body("{}").unwrap()
+/// #     }
+/// # });
+/// let routes = DisabledRoutes::new();
+/// let config = aws_sdk_dynamodb::Config::builder()
+/// #   .test_defaults()
+/// #   .http_client(http_client)
+///     .with_momento_accelerator(
+///         accelerator_config()
+///             .cache_name("my-dynamo-cache")
+///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///             .auth_token("my-momento-auth-token")
+///             .ttl(Duration::from_secs(60))
+///             .disabled_routes(routes.clone()),
+///     )
+///     .build();
+/// let client = aws_sdk_dynamodb::Client::from_conf(config);
+/// // Whether each of these requests was sent to the proxy
+/// let send_all = || async {
+///     let _ = client.get_item().table_name("orders").send().await;
+///     let _ = client.query().table_name("orders").send().await;
+///     let _ = client.get_item().table_name("users").send().await;
+/// #   std::mem::take(&mut *proxied.lock().unwrap())
+/// };
+/// assert_eq!(send_all().await, [true, true, true]);
+///
+/// // During an incident on the orders table, its point reads go directly to DynamoDB
+/// routes.disable(Operation::GetItem, "orders");
+/// assert_eq!(send_all().await, [false, true, true]);
+///
+/// routes.enable(Operation::GetItem, "orders");
+/// assert_eq!(send_all().await, [true, true, true]);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DisabledRoutes {
+    routes: Arc<RwLock<HashSet<(Operation, String)>>>,
+}
+
+impl DisabledRoutes {
+    /// A registry with no disabled routes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send requests for `operation` on `table` directly to DynamoDB.
+    ///
+    /// `table` is matched against the table name (or ARN) as the request uses it.
+    pub fn disable(&self, operation: Operation, table: impl Into<String>) {
+        self.routes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert((operation, table.into()));
+    }
+
+    /// Accelerate requests for `operation` on `table` again.
+    pub fn enable(&self, operation: Operation, table: &str) {
+        self.routes
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(operation, table.to_string()));
+    }
+
+    /// Whether requests for `operation` on `table` go directly to DynamoDB.
+    pub fn is_disabled(&self, operation: Operation, table: &str) -> bool {
+        self.routes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .contains(&(operation, table.to_string()))
+    }
+
+    /// The disabled routes, sorted.
+    pub fn routes(&self) -> Vec<(Operation, String)> {
+        let mut routes: Vec<_> = self
+            .routes
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect();
+        routes.sort_unstable();
+        routes
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+/// The attributes a table's items are expected to have, sent as `x-item-schema` so that a proxy
+/// can validate the items it caches. See
+/// [AcceleratorConfig::table_schema](crate::AcceleratorConfig::table_schema).
+///
+/// # Example
+/// ```
+/// use momento_ddb_interceptor::{AttributeType, SchemaDescriptor};
+///
+/// let schema = SchemaDescriptor::new()
+///     .attribute("customer_id", AttributeType::String)
+///     .attribute("total", AttributeType::Number)
+///     .optional_attribute("tags", AttributeType::StringSet);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDescriptor {
+    /// Each attribute's type, and whether every item has it
+    attributes: BTreeMap<String, (AttributeType, bool)>,
+}
+
+impl SchemaDescriptor {
+    /// A schema without any attributes yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expect every item to have `name`, of type `attribute_type`.
+    pub fn attribute(mut self, name: impl Into<String>, attribute_type: AttributeType) -> Self {
+        self.attributes.insert(name.into(), (attribute_type, true));
+        self
+    }
+
+    /// Expect `name` to be of type `attribute_type` in the items that have it.
+    pub fn optional_attribute(
+        mut self,
+        name: impl Into<String>,
+        attribute_type: AttributeType,
+    ) -> Self {
+        self.attributes.insert(name.into(), (attribute_type, false));
+        self
+    }
+
+    /// The schema as `name:type`, with `?` after the type of an optional attribute, for each
+    /// attribute in name order and separated by `,`. Names are percent-encoded with `encode`.
+    pub(crate) fn descriptor(&self, encode: impl Fn(&str) -> String) -> String {
+        let attributes: Vec<String> = self
+            .attributes
+            .iter()
+            .map(|(name, (attribute_type, required))| {
+                let optional = if *required { "" } else { "?" };
+                format!("{}:{}{optional}", encode(name), attribute_type.as_str())
+            })
+            .collect();
+        attributes.join(",")
+    }
+}
+
+/// The type of an attribute in a [SchemaDescriptor], named by its DynamoDB type descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AttributeType {
+    /// `S`
+    String,
+    /// `N`
+    Number,
+    /// `B`
+    Binary,
+    /// `BOOL`
+    Boolean,
+    /// `NULL`
+    Null,
+    /// `M`
+    Map,
+    /// `L`
+    List,
+    /// `SS`
+    StringSet,
+    /// `NS`
+    NumberSet,
+    /// `BS`
+    BinarySet,
+}
+
+impl AttributeType {
+    /// The DynamoDB type descriptor, like `S` or `BOOL`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeType::String => "S",
+            AttributeType::Number => "N",
+            AttributeType::Binary => "B",
+            AttributeType::Boolean => "BOOL",
+            AttributeType::Null => "NULL",
+            AttributeType::Map => "M",
+            AttributeType::List => "L",
+            AttributeType::StringSet => "SS",
+            AttributeType::NumberSet => "NS",
+            AttributeType::BinarySet => "BS",
+        }
+    }
+}
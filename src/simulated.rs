@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+use crate::ItemKey;
+
+/// An in-process stand-in for the proxy, set up by [ProxyMode::Simulated](crate::ProxyMode::Simulated).
+///
+/// Items are kept per table, newest first. Without the tables' key schemas a put can't tell
+/// which earlier item it replaces, so a get returns the newest live item that matches its key.
+/// Once it holds `capacity` items, the oldest put is dropped to make room for the next.
+#[derive(Debug)]
+pub(crate) struct SimulatedProxy {
+    capacity: usize,
+    tables: Mutex<Tables>,
+}
+
+#[derive(Debug, Default)]
+struct Tables {
+    entries: HashMap<String, Vec<Entry>>,
+    puts: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    item: ItemKey,
+    expires_at: SystemTime,
+    /// Which put this was, to drop the oldest first
+    put: u64,
+}
+
+/// The response the simulated proxy gave the current attempt
+#[derive(Debug, Clone)]
+pub(crate) enum SimulatedResponse {
+    GetItem(Option<ItemKey>),
+    PutItem,
+}
+
+impl Storable for SimulatedResponse {
+    type Storer = StoreReplace<Self>;
+}
+
+impl SimulatedProxy {
+    /// A simulated proxy holding up to `capacity` items.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            tables: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn put(&self, table: &str, item: ItemKey, expires_at: SystemTime, now: SystemTime) {
+        let mut tables = self.tables();
+        tables.puts += 1;
+        let put = tables.puts;
+        let entries = tables.entries.entry(table.to_string()).or_default();
+        entries.retain(|entry| now < entry.expires_at);
+        entries.insert(
+            0,
+            Entry {
+                item,
+                expires_at,
+                put,
+            },
+        );
+        while tables.entries.values().map(Vec::len).sum::<usize>() > self.capacity {
+            // Each table's entries are newest first, so its oldest is last
+            let Some(oldest) = tables
+                .entries
+                .iter()
+                .filter_map(|(table, entries)| Some((table, entries.last()?.put)))
+                .min_by_key(|(_, put)| *put)
+                .map(|(table, _)| table.clone())
+            else {
+                break;
+            };
+            let entries = tables.entries.get_mut(&oldest).expect("just found");
+            entries.pop();
+            if entries.is_empty() {
+                tables.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// The newest item of `table` that has every attribute of `key`, unless it has expired by `now`.
+    pub(crate) fn get(&self, table: &str, key: &ItemKey, now: SystemTime) -> Option<ItemKey> {
+        let tables = self.tables();
+        tables
+            .entries
+            .get(table)?
+            .iter()
+            .filter(|entry| now < entry.expires_at)
+            .find(|entry| {
+                key.iter()
+                    .all(|(name, value)| entry.item.get(name) == Some(value))
+            })
+            .map(|entry| entry.item.clone())
+    }
+
+    fn tables(&self) -> std::sync::MutexGuard<'_, Tables> {
+        self.tables.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The error that stops a request from being sent when the simulated proxy answered it.
+///
+/// It never reaches the caller: the simulated response replaces it before the attempt completes.
+#[derive(Debug)]
+pub(crate) struct SimulatedHit;
+
+impl std::fmt::Display for SimulatedHit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("served by the simulated proxy")
+    }
+}
+
+impl std::error::Error for SimulatedHit {}
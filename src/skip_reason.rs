@@ -0,0 +1,428 @@
+use std::fmt::Display;
+
+use crate::Operation;
+
+/// Why a request was sent directly to DynamoDB instead of through the proxy.
+///
+/// Skipped requests are logged at debug level with their reason, and the reason is available
+/// from [AcceleratorResponse::skip_reason](crate::AcceleratorResponse::skip_reason).
+///
+/// # Example
+/// ```
+/// # use momento_ddb_interceptor::test_support::TestDefaults;
+/// # use aws_sdk_dynamodb::config::retry::RetryConfig;
+/// # use aws_smithy_http_client::test_util::infallible_client_fn;
+/// use momento_ddb_interceptor::{
+///     AcceleratorConfig, AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator,
+///     SkipReason, accelerator_config,
+/// };
+/// use std::time::Duration;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let accelerator = || {
+///     accelerator_config()
+///         .cache_name("my-dynamo-cache")
+///         .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+///         .auth_token("my-momento-auth-token")
+///         .ttl(Duration::from_secs(60))
+/// };
+/// let skip_reason = async |accelerator: AcceleratorConfig| {
+/// #   // The proxy is down, DynamoDB is fine
+/// #   let http_client = infallible_client_fn(|request| {
+/// #       let to_proxy = request.uri().host() == Some("api.cache.cell-us-west-2-1.prod.a.momentohq.com");
+/// #       let status = if to_proxy { 500 } else { 200 };
+/// #       http::Response::builder().status(status).body("{}").unwrap()
+/// #   });
+///     let config = aws_sdk_dynamodb::Config::builder()
+/// #       .test_defaults()
+/// #       .http_client(http_client)
+/// #       .retry_config(RetryConfig::disabled())
+///         .with_momento_accelerator(accelerator)
+///         .build();
+///     let client = aws_sdk_dynamodb::Client::from_conf(config);
+///     let mut skip_reasons = Vec::new();
+///     for _ in 0..2 {
+///         let response = AcceleratorResponse::new();
+///         let _ = client
+///             .get_item()
+///             .table_name("ledger")
+///             .customize()
+///             .capture_accelerator_response(&response)
+///             .send()
+///             .await;
+///         skip_reasons.push(response.skip_reason());
+///     }
+///     skip_reasons
+/// };
+///
+/// assert_eq!(skip_reason(accelerator()).await, [None, None]);
+/// assert_eq!(
+///     skip_reason(accelerator().enabled(false)).await,
+///     [Some(SkipReason::Disabled), Some(SkipReason::Disabled)],
+/// );
+/// assert_eq!(
+///     skip_reason(accelerator().deny_tables(["ledger".to_string()])).await,
+///     [
+///         Some(SkipReason::DeniedTable { table: "ledger".to_string() }),
+///         Some(SkipReason::DeniedTable { table: "ledger".to_string() }),
+///     ],
+/// );
+/// // The first request trips the breaker
+/// assert_eq!(
+///     skip_reason(accelerator().circuit_breaker(1, Duration::from_secs(30))).await,
+///     [None, Some(SkipReason::CircuitOpen)],
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(tag = "reason", rename_all = "snake_case")
+)]
+#[non_exhaustive]
+pub enum SkipReason {
+    /// The accelerator is turned off with [enabled](crate::AcceleratorConfig::enabled).
+    Disabled,
+    /// The accelerator has been [shut down](crate::ProxyInterceptor::shutdown).
+    ShutDown,
+    /// The interceptor was created from a configuration that
+    /// [build](crate::AcceleratorConfig::build) rejects, so it never uses the proxy.
+    InvalidConfig {
+        /// What [build](crate::AcceleratorConfig::build) reports
+        message: String,
+    },
+    /// The accelerator is still warming up, see
+    /// [warmup_requests](crate::AcceleratorConfig::warmup_requests).
+    Warmup,
+    /// The [circuit breaker](crate::AcceleratorConfig::circuit_breaker) is open.
+    CircuitOpen,
+    /// The proxy asked clients to back off, see
+    /// [respect_retry_after](crate::AcceleratorConfig::respect_retry_after).
+    ProxyOverloaded,
+    /// [max_inflight](crate::AcceleratorConfig::max_inflight) requests are already at the proxy.
+    InflightLimit,
+    /// The request's HTTP method is not in [proxy_methods](crate::AcceleratorConfig::proxy_methods).
+    MethodNotProxied {
+        /// The request's method
+        method: String,
+    },
+    /// The client's region is not one of the
+    /// [enabled_regions](crate::AcceleratorConfig::enabled_regions).
+    RegionNotEnabled {
+        /// The client's region, if it has one
+        region: Option<String>,
+    },
+    /// The request refers to a table in [deny_tables](crate::AcceleratorConfig::deny_tables).
+    DeniedTable {
+        /// The denied table
+        table: String,
+    },
+    /// The request's operation is disabled for one of its tables in the
+    /// [disabled_routes](crate::AcceleratorConfig::disabled_routes).
+    DisabledRoute {
+        /// The operation
+        operation: Operation,
+        /// The table the operation is disabled for
+        table: String,
+    },
+    /// The [route_if](crate::AcceleratorConfig::route_if) predicate rejected the request.
+    NotRouted,
+    /// One of the request's tables gets fewer requests than
+    /// [min_request_rate](crate::AcceleratorConfig::min_request_rate).
+    LowRequestRate {
+        /// The table
+        table: String,
+    },
+    /// The proxy has already been sent as many requests this window as the
+    /// [cost_budget](crate::AcceleratorConfig::cost_budget) allows.
+    CostBudgetExhausted,
+    /// The previous attempt went to the proxy and took longer than
+    /// [hit_timeout](crate::AcceleratorConfig::hit_timeout).
+    HitTimeout,
+    /// The request asks for its consumed capacity, which a cached response can't report, see
+    /// [bypass_when_capacity_requested](crate::AcceleratorConfig::bypass_when_capacity_requested).
+    CapacityRequested,
+    /// The operation manages tables or other resources rather than items, see
+    /// [proxy_control_plane](crate::AcceleratorConfig::proxy_control_plane).
+    ControlPlane {
+        /// The operation
+        operation: Operation,
+    },
+    /// A BatchExecuteStatement has a statement other than a `SELECT`.
+    ///
+    /// Caching part of a batch isn't safe, so only batches of reads are proxied, with the number
+    /// of statements sent as `x-partiql-batch` for the proxy to cache each one.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use aws_sdk_dynamodb::types::BatchStatementRequest;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, SkipReason,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let batch_headers = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let batch_headers = batch_headers.clone();
+    /// #     move |request| {
+    /// #         let header = request.headers().get("x-partiql-batch");
+    /// #         batch_headers.lock().unwrap().push(header.map(|h| h.to_str().unwrap().to_string()));
+    /// #         http::Response::builder().body(r#"{"Responses": []}"#).unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    /// let statement = |statement: &str| {
+    ///     BatchStatementRequest::builder().statement(statement).build().unwrap()
+    /// };
+    ///
+    /// let reads = AcceleratorResponse::new();
+    /// client
+    ///     .batch_execute_statement()
+    ///     .statements(statement(r#"SELECT * FROM "users" WHERE id = 'u-1'"#))
+    ///     .statements(statement(r#"SELECT * FROM "users" WHERE id = 'u-2'"#))
+    ///     .customize()
+    ///     .capture_accelerator_response(&reads)
+    ///     .send()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(reads.skip_reason(), None);
+    ///
+    /// let mixed = AcceleratorResponse::new();
+    /// client
+    ///     .batch_execute_statement()
+    ///     .statements(statement(r#"SELECT * FROM "users" WHERE id = 'u-1'"#))
+    ///     .statements(statement(r#"DELETE FROM "users" WHERE id = 'u-2'"#))
+    ///     .customize()
+    ///     .capture_accelerator_response(&mixed)
+    ///     .send()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(mixed.skip_reason(), Some(SkipReason::BatchHasWrites));
+    ///
+    /// # assert_eq!(*batch_headers.lock().unwrap(), [Some("2".to_string()), None]);
+    /// # }
+    /// ```
+    BatchHasWrites,
+    /// The request already targets the proxy host, so it is sent as it is rather than rewritten
+    /// a second time.
+    ///
+    /// This usually means the client's endpoint points at the proxy; use
+    /// [proxy_host_from_endpoint](crate::AcceleratorConfig::proxy_host_from_endpoint) for that.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::capture_request;
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, SkipReason,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let (http_client, request) = capture_request(None);
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .endpoint_url("https://api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::from_secs(60)),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// let _ = client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await;
+    ///
+    /// let request = request.expect_request();
+    /// assert_eq!(request.uri(), "https://api.cache.cell-us-west-2-1.prod.a.momentohq.com/");
+    /// assert_eq!(request.headers().get("x-uri"), None);
+    /// assert_eq!(
+    ///     response.skip_reason(),
+    ///     Some(SkipReason::AlreadyProxied {
+    ///         host: "api.cache.cell-us-west-2-1.prod.a.momentohq.com".to_string(),
+    ///     }),
+    /// );
+    /// # }
+    /// ```
+    AlreadyProxied {
+        /// The proxy host the request targets
+        host: String,
+    },
+    /// The request's TTL is zero, which means its response must not be cached.
+    ///
+    /// This applies to a TTL from any source, like [ttl](crate::AcceleratorConfigBuilder::ttl),
+    /// [set_ttl](crate::ProxyInterceptor::set_ttl), or
+    /// [with_dynamic_ttl](crate::AcceleratorRequestExt::with_dynamic_ttl), once it is clamped to
+    /// [min_ttl](crate::AcceleratorConfig::min_ttl) and would be sent as 0. A request sent
+    /// [without_accelerator_ttl](crate::AcceleratorRequestExt::without_accelerator_ttl) has no
+    /// TTL, and is still proxied.
+    ///
+    /// # Example
+    /// ```
+    /// # use momento_ddb_interceptor::test_support::TestDefaults;
+    /// # use aws_smithy_http_client::test_util::infallible_client_fn;
+    /// # use std::sync::{Arc, Mutex};
+    /// use momento_ddb_interceptor::{
+    ///     AcceleratorRequestExt, AcceleratorResponse, MomentoAccelerator, SkipReason,
+    ///     accelerator_config,
+    /// };
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// # let hosts = Arc::new(Mutex::new(Vec::new()));
+    /// # let http_client = infallible_client_fn({
+    /// #     let hosts = hosts.clone();
+    /// #     move |request| {
+    /// #         hosts.lock().unwrap().push(request.uri().host().unwrap().to_string());
+    /// #         http::Response::builder().body("{}").unwrap()
+    /// #     }
+    /// # });
+    /// let config = aws_sdk_dynamodb::Config::builder()
+    /// #   .test_defaults()
+    /// #   .http_client(http_client)
+    ///     .with_momento_accelerator(
+    ///         accelerator_config()
+    ///             .cache_name("my-dynamo-cache")
+    ///             .momento_hostname("api.cache.cell-us-west-2-1.prod.a.momentohq.com")
+    ///             .auth_token("my-momento-auth-token")
+    ///             .ttl(Duration::ZERO),
+    ///     )
+    ///     .build();
+    /// let client = aws_sdk_dynamodb::Client::from_conf(config);
+    ///
+    /// let response = AcceleratorResponse::new();
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .capture_accelerator_response(&response)
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    /// assert_eq!(response.skip_reason(), Some(SkipReason::ZeroTtl));
+    ///
+    /// // A TTL of its own, or none at all, sends it through the proxy again
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .with_dynamic_ttl(|_| Duration::from_secs(5))
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    /// client
+    ///     .get_item()
+    ///     .table_name("my-table")
+    ///     .customize()
+    ///     .without_accelerator_ttl()
+    ///     .send()
+    ///     .await
+    ///     .expect("get item");
+    ///
+    /// assert_eq!(
+    ///     *hosts.lock().unwrap(),
+    ///     [
+    ///         "dynamodb.us-west-2.amazonaws.com",
+    ///         "api.cache.cell-us-west-2-1.prod.a.momentohq.com",
+    ///         "api.cache.cell-us-west-2-1.prod.a.momentohq.com",
+    ///     ],
+    /// );
+    /// # }
+    /// ```
+    ZeroTtl,
+    /// The request couldn't be sent to the proxy, and
+    /// [on_runtime_error](crate::AcceleratorConfig::on_runtime_error) chose to fail open.
+    RuntimeError {
+        /// What went wrong
+        message: String,
+    },
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::Disabled => write!(f, "accelerator is disabled"),
+            SkipReason::ShutDown => write!(f, "accelerator is shut down"),
+            SkipReason::InvalidConfig { message } => {
+                write!(f, "accelerator config is invalid: {message}")
+            }
+            SkipReason::Warmup => write!(f, "accelerator is warming up"),
+            SkipReason::CircuitOpen => write!(f, "circuit breaker is open"),
+            SkipReason::ProxyOverloaded => write!(f, "proxy is overloaded"),
+            SkipReason::InflightLimit => write!(f, "too many requests are in flight to the proxy"),
+            SkipReason::MethodNotProxied { method } => write!(f, "method {method} is not proxied"),
+            SkipReason::RegionNotEnabled {
+                region: Some(region),
+            } => {
+                write!(f, "region {region} is not enabled")
+            }
+            SkipReason::RegionNotEnabled { region: None } => {
+                write!(
+                    f,
+                    "client has no region to check against the enabled regions"
+                )
+            }
+            SkipReason::DeniedTable { table } => write!(f, "table {table} is denied"),
+            SkipReason::DisabledRoute { operation, table } => {
+                write!(f, "{operation} is disabled for table {table}")
+            }
+            SkipReason::NotRouted => write!(f, "request does not match the route predicate"),
+            SkipReason::LowRequestRate { table } => {
+                write!(f, "table {table} is below the minimum request rate")
+            }
+            SkipReason::CostBudgetExhausted => {
+                write!(
+                    f,
+                    "the cost budget for proxied requests is spent for this window"
+                )
+            }
+            SkipReason::HitTimeout => write!(f, "the proxied attempt exceeded the hit timeout"),
+            SkipReason::CapacityRequested => write!(f, "request asks for consumed capacity"),
+            SkipReason::ControlPlane { operation } => {
+                write!(f, "{operation} is a control plane operation")
+            }
+            SkipReason::BatchHasWrites => {
+                write!(f, "statement batch has statements that aren't reads")
+            }
+            SkipReason::AlreadyProxied { host } => {
+                write!(f, "request already targets the proxy host {host}")
+            }
+            SkipReason::ZeroTtl => write!(f, "request has a TTL of zero"),
+            SkipReason::RuntimeError { message } => {
+                write!(f, "request could not be proxied: {message}")
+            }
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+
+/// How the accelerator handled the requests for one table, from
+/// [ProxyInterceptor::per_table_stats](crate::ProxyInterceptor::per_table_stats).
+///
+/// These count the same events as [AcceleratorMetrics](crate::AcceleratorMetrics): a request
+/// that skips the proxy is a bypass, and one the proxy failed to answer is unavailable, so
+/// neither is a miss. A request naming several tables, like a BatchGetItem, counts for each.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AcceleratorStats {
+    /// Responses served from the cache
+    pub hits: u64,
+    /// Responses the proxy had to read from DynamoDB
+    pub misses: u64,
+    /// Requests sent directly to DynamoDB instead of the proxy
+    pub bypasses: u64,
+    /// Attempts the proxy got no response or a server error for
+    pub proxy_unavailable: u64,
+}
+
+impl AcceleratorStats {
+    /// The fraction of responses that were served from the cache, or `None` before any were.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let responses = self.hits + self.misses;
+        (responses > 0).then(|| self.hits as f64 / responses as f64)
+    }
+}
+
+/// How much per-table and per-request state the interceptor keeps in memory, set by
+/// [state_limits](crate::AcceleratorConfig::state_limits).
+///
+/// Each limit is a number of entries. Once one is reached, the least recently used entry is
+/// dropped to make room. Limits below 1 are treated as 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateLimits {
+    pub(crate) tables: usize,
+    pub(crate) requests: usize,
+    pub(crate) simulated_items: usize,
+}
+
+impl Default for StateLimits {
+    fn default() -> Self {
+        Self {
+            tables: 1_000,
+            requests: 10_000,
+            simulated_items: 10_000,
+        }
+    }
+}
+
+impl StateLimits {
+    /// The default limits: 1,000 tables, 10,000 requests, and 10,000 simulated items.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep [per_table_stats](crate::ProxyInterceptor::per_table_stats) for up to `tables`
+    /// tables.
+    pub fn tables(mut self, tables: usize) -> Self {
+        self.tables = tables.max(1);
+        self
+    }
+
+    /// Count the reads of up to `requests` distinct requests for
+    /// [access_hint_thresholds](crate::AcceleratorConfig::access_hint_thresholds).
+    pub fn requests(mut self, requests: usize) -> Self {
+        self.requests = requests.max(1);
+        self
+    }
+
+    /// Keep up to `items` items, across every table, in the
+    /// [simulated proxy](crate::ProxyMode::Simulated).
+    pub fn simulated_items(mut self, items: usize) -> Self {
+        self.simulated_items = items.max(1);
+        self
+    }
+}
+
+/// [AcceleratorStats] for the tables the interceptor most recently saw requests for.
+#[derive(Debug)]
+pub(crate) struct TableStats {
+    tables: Mutex<Lru<AcceleratorStats>>,
+}
+
+impl TableStats {
+    /// Stats for up to `capacity` tables.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            tables: Mutex::new(Lru::new(capacity)),
+        }
+    }
+
+    /// Apply `count` to the stats of each of `tables`.
+    pub(crate) fn record<'a>(
+        &self,
+        tables: impl IntoIterator<Item = &'a str>,
+        count: impl Fn(&mut AcceleratorStats),
+    ) {
+        let mut stats = self.tables.lock().unwrap_or_else(PoisonError::into_inner);
+        for table in tables {
+            count(stats.get_or_insert(table));
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<String, AcceleratorStats> {
+        let stats = self.tables.lock().unwrap_or_else(PoisonError::into_inner);
+        stats
+            .entries
+            .iter()
+            .map(|(table, (table_stats, _))| (table.clone(), *table_stats))
+            .collect()
+    }
+}
+
+/// A map that drops its least recently used entry to stay within its capacity.
+#[derive(Debug)]
+struct Lru<V> {
+    capacity: usize,
+    /// Each value with the use it was last touched by
+    entries: HashMap<String, (V, u64)>,
+    /// The key of each entry by the use it was last touched by, least recent first
+    recency: BTreeMap<u64, String>,
+    uses: u64,
+}
+
+impl<V: Default> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            uses: 0,
+        }
+    }
+
+    /// The value for `key`, inserting the default if there is none.
+    fn get_or_insert(&mut self, key: &str) -> &mut V {
+        self.uses += 1;
+        let uses = self.uses;
+        if let Some((_, last_used)) = self.entries.get_mut(key) {
+            let key = self
+                .recency
+                .remove(last_used)
+                .expect("every entry has a use");
+            *last_used = uses;
+            self.recency.insert(uses, key);
+        } else {
+            if self.capacity <= self.entries.len()
+                && let Some((_, least_recent)) = self.recency.pop_first()
+            {
+                self.entries.remove(&least_recent);
+            }
+            self.entries.insert(key.to_string(), (V::default(), uses));
+            self.recency.insert(uses, key.to_string());
+        }
+        &mut self.entries.get_mut(key).expect("just inserted").0
+    }
+}
+
+/// How many times the interceptor has proxied each of the reads it most recently saw, for
+/// [access_hint_thresholds](crate::AcceleratorConfig::access_hint_thresholds).
+#[derive(Debug)]
+pub(crate) struct AccessCounts {
+    requests: Mutex<Lru<u64>>,
+}
+
+impl AccessCounts {
+    /// Counts for up to `capacity` requests.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            requests: Mutex::new(Lru::new(capacity)),
+        }
+    }
+
+    /// Count another read of the request with `request_hash`, returning the count including it.
+    pub(crate) fn increment(&self, request_hash: &str) -> u64 {
+        let mut requests = self.requests.lock().unwrap_or_else(PoisonError::into_inner);
+        let count = requests.get_or_insert(request_hash);
+        *count += 1;
+        *count
+    }
+}
+
+/// The recent request rate of each of the tables the interceptor most recently saw, for
+/// [min_request_rate](crate::AcceleratorConfig::min_request_rate).
+///
+/// Requests are counted in fixed windows. A table's rate is estimated from its count in the
+/// current window plus the part of the previous window's count that is still within one window
+/// of now, which smooths out the jump at each window boundary.
+#[derive(Debug)]
+pub(crate) struct RequestRates {
+    window: Duration,
+    tables: Mutex<Lru<WindowCounts>>,
+}
+
+/// A table's requests in the current window and the one before it
+#[derive(Debug, Default)]
+struct WindowCounts {
+    /// The number of the current window since the Unix epoch
+    window: u128,
+    current: u64,
+    previous: u64,
+}
+
+impl RequestRates {
+    /// Rates over `window` for up to `capacity` tables.
+    pub(crate) fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            tables: Mutex::new(Lru::new(capacity)),
+        }
+    }
+
+    /// Count a request for each of `tables` at `now`, returning the first of them whose rate
+    /// including it is under `per_second`.
+    pub(crate) fn record_below<'a>(
+        &self,
+        tables: impl IntoIterator<Item = &'a str>,
+        now: SystemTime,
+        per_second: f64,
+    ) -> Option<&'a str> {
+        let window = self.window.as_nanos().max(1);
+        let since_epoch = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let (index, into_window) = (since_epoch / window, since_epoch % window);
+        let previous_weight = 1.0 - into_window as f64 / window as f64;
+        let mut rates = self.tables.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut below = None;
+        for table in tables {
+            let counts = rates.get_or_insert(table);
+            if counts.window != index {
+                counts.previous = if counts.window + 1 == index {
+                    counts.current
+                } else {
+                    0
+                };
+                counts.current = 0;
+                counts.window = index;
+            }
+            counts.current += 1;
+            let requests = counts.previous as f64 * previous_weight + counts.current as f64;
+            if below.is_none() && requests / self.window.as_secs_f64() < per_second {
+                below = Some(table);
+            }
+        }
+        below
+    }
+}
+
+/// The tables a proxied attempt is for, to count its response against
+#[derive(Debug, Clone)]
+pub(crate) struct ProxiedTables(pub(crate) Vec<String>);
+
+impl Storable for ProxiedTables {
+    type Storer = StoreReplace<Self>;
+}
@@ -0,0 +1,32 @@
+use std::collections::BTreeSet;
+
+/// Which tables a configuration accelerates, from
+/// [AcceleratorConfig::accelerated_tables](crate::AcceleratorConfig::accelerated_tables).
+///
+/// This only reflects the options of the configuration that choose tables. Requests for an
+/// accelerated table can still skip the proxy for other reasons, like an open circuit breaker.
+/// When an option decides per request which tables are accelerated, the selection is
+/// [Filtered](Self::Filtered), since those can't be listed from the configuration alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TableSelection {
+    /// Every table is accelerated.
+    All,
+    /// Every table is accelerated except these, set with
+    /// [deny_tables](crate::AcceleratorConfig::deny_tables).
+    AllExcept(BTreeSet<String>),
+    /// Tables outside `except` are accelerated only for the requests that pass `filters`.
+    Filtered {
+        /// The tables never accelerated, set with
+        /// [deny_tables](crate::AcceleratorConfig::deny_tables)
+        except: BTreeSet<String>,
+        /// The options that decide per request, by name, out of
+        /// [route_if](crate::AcceleratorConfig::route_if),
+        /// [disabled_routes](crate::AcceleratorConfig::disabled_routes) and
+        /// [enabled_regions](crate::AcceleratorConfig::enabled_regions)
+        filters: Vec<&'static str>,
+    },
+    /// No table is accelerated, because the accelerator is
+    /// [disabled](crate::AcceleratorConfig::enabled).
+    None,
+}
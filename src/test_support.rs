@@ -0,0 +1,17 @@
+//! Setup shared by this crate's examples, which isn't part of its API.
+
+use aws_sdk_dynamodb::config::{BehaviorVersion, Credentials, Region};
+
+/// Fills in what every example's DynamoDB config needs but doesn't show.
+pub trait TestDefaults {
+    /// The latest behavior version, the `us-west-2` region and static test credentials.
+    fn test_defaults(self) -> Self;
+}
+
+impl TestDefaults for aws_sdk_dynamodb::config::Builder {
+    fn test_defaults(self) -> Self {
+        self.behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-west-2"))
+            .credentials_provider(Credentials::new("akid", "secret", None, None, "test"))
+    }
+}
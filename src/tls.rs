@@ -0,0 +1,163 @@
+use aws_sdk_dynamodb::config::SharedHttpClient;
+use aws_smithy_http_client::hyper_014::HyperClientBuilder;
+
+/// How the accelerator's own HTTPS client talks to the proxy.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TlsOptions {
+    /// Refuse to negotiate anything older than TLS 1.3
+    pub(crate) tls13_only: bool,
+    /// Only accept these certificates from the proxy
+    #[cfg(feature = "cert-pinning")]
+    pub(crate) pinned: Option<pinning::PinnedCertificates>,
+    /// The log target of the accelerator
+    #[cfg(feature = "cert-pinning")]
+    pub(crate) logs_to: String,
+}
+
+/// An HTTPS client with `options`.
+///
+/// Outside of the options, this matches the SDK's default client: the platform's root
+/// certificates, and HTTP/1.1 or HTTP/2.
+pub(crate) fn http_client(options: &TlsOptions) -> SharedHttpClient {
+    let versions: &[&rustls::SupportedProtocolVersion] = if options.tls13_only {
+        &[&rustls::version::TLS13]
+    } else {
+        rustls::DEFAULT_VERSIONS
+    };
+    let builder = rustls::ClientConfig::builder()
+        .with_safe_default_cipher_suites()
+        .with_safe_default_kx_groups()
+        .with_protocol_versions(versions)
+        .expect("the default cipher suites support every protocol version");
+    #[cfg(feature = "cert-pinning")]
+    if let Some(pinned) = &options.pinned {
+        let verifier = pinning::PinningVerifier::new(pinned.clone(), &options.logs_to);
+        let tls_config = builder
+            .with_custom_certificate_verifier(std::sync::Arc::new(verifier))
+            .with_no_client_auth();
+        return https_client(tls_config);
+    }
+    let tls_config =
+        hyper_rustls::ConfigBuilderExt::with_native_roots(builder).with_no_client_auth();
+    https_client(tls_config)
+}
+
+fn https_client(tls_config: rustls::ClientConfig) -> SharedHttpClient {
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .build();
+    HyperClientBuilder::new().build(connector)
+}
+
+#[cfg(feature = "cert-pinning")]
+pub(crate) mod pinning {
+    use std::net::IpAddr;
+    use std::time::SystemTime;
+
+    use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+    use rustls::{Certificate, RootCertStore, ServerName};
+
+    /// The SHA-256 fingerprints the proxy's certificate must match.
+    #[derive(Debug, Clone)]
+    pub(crate) struct PinnedCertificates {
+        /// The proxy host the pins apply to
+        pub(crate) host: String,
+        pub(crate) fingerprints: Vec<[u8; 32]>,
+    }
+
+    /// Parse a SHA-256 fingerprint written in hex, with or without `:` between the bytes, like
+    /// `openssl x509 -noout -fingerprint -sha256` prints it.
+    pub(crate) fn parse_fingerprint(fingerprint: &str) -> Option<[u8; 32]> {
+        let hex: Vec<u8> = fingerprint.bytes().filter(|byte| *byte != b':').collect();
+        if hex.len() != 64 {
+            return None;
+        }
+        let mut parsed = [0; 32];
+        for (byte, digits) in parsed.iter_mut().zip(hex.chunks(2)) {
+            *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(parsed)
+    }
+
+    /// Verifies the proxy's certificate by its fingerprint, and every other server's as usual.
+    pub(crate) struct PinningVerifier {
+        pinned: PinnedCertificates,
+        webpki: WebPkiVerifier,
+    }
+
+    impl PinningVerifier {
+        /// A verifier of `pinned`, trusting the platform's root certificates for every other
+        /// server.
+        ///
+        /// If those can't be loaded, no other server is trusted, so requests that skip the proxy
+        /// fail the handshake instead of this panicking. The proxy is still verified by its pins.
+        pub(crate) fn new(pinned: PinnedCertificates, logs_to: &str) -> Self {
+            let mut roots = RootCertStore::empty();
+            match rustls_native_certs::load_native_certs() {
+                Ok(native_certs) => {
+                    let native_certs: Vec<_> =
+                        native_certs.into_iter().map(|cert| cert.0).collect();
+                    roots.add_parsable_certificates(&native_certs);
+                }
+                Err(e) => log::error!(
+                    target: logs_to,
+                    "could not load the platform's root certificates, so only the pinned proxy at {} can be verified: {e}",
+                    pinned.host
+                ),
+            }
+            Self {
+                pinned,
+                webpki: WebPkiVerifier::new(roots, None),
+            }
+        }
+
+        fn is_proxy(&self, server_name: &ServerName) -> bool {
+            match server_name {
+                ServerName::DnsName(name) => name.as_ref().eq_ignore_ascii_case(&self.pinned.host),
+                ServerName::IpAddress(ip) => self.pinned.host.parse::<IpAddr>() == Ok(*ip),
+                _ => false,
+            }
+        }
+    }
+
+    impl ServerCertVerifier for PinningVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            if !self.is_proxy(server_name) {
+                // Requests that skip the proxy go to DynamoDB over the same client
+                return self.webpki.verify_server_cert(
+                    end_entity,
+                    intermediates,
+                    server_name,
+                    scts,
+                    ocsp_response,
+                    now,
+                );
+            }
+            let fingerprint = ring::digest::digest(&ring::digest::SHA256, &end_entity.0);
+            if self
+                .pinned
+                .fingerprints
+                .iter()
+                .any(|pinned| pinned[..] == *fingerprint.as_ref())
+            {
+                Ok(ServerCertVerified::assertion())
+            } else {
+                Err(rustls::Error::General(format!(
+                    "the certificate of {} does not match a pinned fingerprint",
+                    self.pinned.host
+                )))
+            }
+        }
+    }
+}
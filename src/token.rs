@@ -0,0 +1,49 @@
+use std::time::{Duration, SystemTime};
+
+use aws_smithy_types::{Document, Number, base64};
+
+use crate::json;
+
+/// When a Momento auth token expires, if it says so.
+///
+/// Momento API keys are base64 JSON holding the key as a JWT in `api_key`, and older auth tokens
+/// are the JWT itself. The JWT's `exp` claim is the expiry, in epoch seconds. Anything else,
+/// including a JWT without an `exp`, is treated as never expiring.
+pub(crate) fn expiry(token: &str) -> Option<SystemTime> {
+    let segments: Vec<&str> = token.split('.').collect();
+    match segments[..] {
+        [_header, claims, _signature] => {
+            let claims = json::parse(&decode(claims)?)?;
+            let seconds = match json::field(&claims, "exp")? {
+                Document::Number(Number::PosInt(seconds)) => *seconds as f64,
+                Document::Number(Number::Float(seconds)) if *seconds >= 0.0 => *seconds,
+                _ => return None,
+            };
+            SystemTime::UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+        }
+        _ => {
+            let key = json::parse(&decode(token)?)?;
+            match json::field(&key, "api_key")? {
+                Document::String(api_key) if api_key.contains('.') => expiry(api_key),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Decode base64 with or without padding, in either the standard or the URL-safe alphabet.
+fn decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut standard: String = encoded
+        .trim_end_matches('=')
+        .chars()
+        .map(|char| match char {
+            '-' => '+',
+            '_' => '/',
+            char => char,
+        })
+        .collect();
+    while !standard.len().is_multiple_of(4) {
+        standard.push('=');
+    }
+    base64::decode(standard).ok()
+}
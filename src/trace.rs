@@ -0,0 +1,44 @@
+use aws_sdk_dynamodb::config::http::HttpResponse;
+use aws_smithy_types::config_bag::{Storable, StoreReplace};
+use tracing::field::Empty;
+
+use crate::Operation;
+
+/// The span of a proxied attempt that was sampled for tracing, see
+/// [trace_sample_rate](crate::AcceleratorConfig::trace_sample_rate).
+///
+/// The span closes when the attempt completes and this is dropped.
+#[derive(Debug, Clone)]
+pub(crate) struct ProxySpan(tracing::Span);
+
+impl Storable for ProxySpan {
+    type Storer = StoreReplace<Self>;
+}
+
+impl ProxySpan {
+    pub(crate) fn start(operation: Option<Operation>, tables: &[&str]) -> Self {
+        Self(tracing::info_span!(
+            target: crate::LOG_TARGET,
+            "momento_accelerator.proxy",
+            operation = operation.map(|operation| operation.name()),
+            tables = tables.join(","),
+            status = Empty,
+            cache_status = Empty,
+        ))
+    }
+
+    /// Record the proxy's cache status, before
+    /// [strip_proxy_headers](crate::AcceleratorConfig::strip_proxy_headers) removes it.
+    pub(crate) fn record_cache_status(&self, response: &HttpResponse) {
+        if let Some(cache_status) = response.headers().get("x-cache-status") {
+            self.0.record("cache_status", cache_status);
+        }
+    }
+
+    /// Record what the proxy answered with, or nothing if it didn't answer.
+    pub(crate) fn finish(&self, response: Option<&HttpResponse>) {
+        if let Some(response) = response {
+            self.0.record("status", response.status().as_u16());
+        }
+    }
+}